@@ -36,7 +36,22 @@ esac
 
 transfer-ownership() {
   sender=$1
-  msg='{"transfer_ownership":{}}'
+  new_owner=$2
+  msg='{"transfer_ownership":{"new_owner":{"address":"'$new_owner'"}}}'
+  execute "$sender" "$msg"
+}
+
+
+accept-ownership() {
+  sender=$1
+  msg='{"accept_ownership":{}}'
+  execute "$sender" "$msg"
+}
+
+
+execute() {
+  sender=$1
+  msg=$2
   flags="\
   --node $NODE \
   --gas-prices 0.025$DENOM \
@@ -67,7 +82,10 @@ echo "executing $CMD for $CONTRACT_ADDR"
 
 case $CMD in
   transfer-ownership)
-    transfer-ownership $1
+    transfer-ownership $1 $2
+    ;;
+  accept-ownership)
+    accept-ownership $1
     ;;
   query-select) 
     query-select
@@ -7,13 +7,13 @@ use cosmwasm_std::Coin;
 use cw_thread::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 fn main() {
-  let mut out_dir = current_dir().unwrap();
-  out_dir.push("schema");
-  create_dir_all(&out_dir).unwrap();
-  remove_schemas(&out_dir).unwrap();
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
 
-  export_schema(&schema_for!(InstantiateMsg), &out_dir);
-  export_schema(&schema_for!(ExecuteMsg), &out_dir);
-  export_schema(&schema_for!(QueryMsg), &out_dir);
-  export_schema(&schema_for!(Coin), &out_dir);
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(Coin), &out_dir);
 }
@@ -1,20 +1,35 @@
 use crate::error::ContractError;
-use crate::execute::delete_node::exec_delete_node;
+use crate::execute::ban::{exec_ban, exec_unban};
+use crate::execute::delete_node::{exec_continue_deletion, exec_delete_node};
 use crate::execute::edit_node::exec_edit_node;
 use crate::execute::flags::{exec_flag, exec_unflag};
 use crate::execute::lifecycle::{exec_resume, exec_setup, exec_suspend, exec_teardown};
+use crate::execute::lock::{exec_lock, exec_unlock};
+use crate::execute::migration::exec_continue_migration;
+use crate::execute::moderation::{exec_resolve_flags, exec_unhide};
+use crate::execute::moderators::exec_update_moderators;
+use crate::execute::ownership::{
+    exec_accept_ownership, exec_cancel_ownership_transfer, exec_transfer_ownership,
+};
+use crate::execute::recycle_bin::exec_restore;
 use crate::execute::reply::exec_reply;
 use crate::execute::set_config::exec_set_config;
 use crate::execute::tip::exec_tip;
 use crate::execute::toggle_save::exec_toggle_save;
 use crate::execute::vote::{exec_vote, exec_votes};
 use crate::execute::Context;
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, NodesQueryMsg, QueryMsg};
+use crate::migrations;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, NodesQueryMsg, QueryMsg, TagFilter};
+use crate::query::bans::query_bans;
+use crate::query::config::{query_config, query_version};
 use crate::query::info::query_thread_info;
 use crate::query::nodes::{
-    query_ancestor_nodes, query_child_nodes, query_nodes_by_id, query_nodes_by_tag_or_mention,
-    TagWrapper,
+    query_ancestor_nodes, query_child_nodes, query_deleted_nodes, query_flagged_nodes,
+    query_nodes_by_author, query_nodes_by_id, query_nodes_by_tag_or_mention, query_revisions,
+    query_saved_nodes, query_subtree,
 };
+use crate::query::permissions::query_permissions;
+use crate::query::votes::{query_voters, query_votes};
 use crate::query::ReadonlyContext;
 use crate::state;
 use cosmwasm_std::{entry_point, to_json_binary};
@@ -22,8 +37,8 @@ use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response};
 use cw2::set_contract_version;
 use cw_table::lifecycle::LifecycleExecuteMsg;
 
-const CONTRACT_NAME: &str = "crates.io:cw-thread";
-const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const CONTRACT_NAME: &str = "crates.io:cw-thread";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Instantiates a new discussion thread contract.
 ///
@@ -41,17 +56,27 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(state::init(Context { deps, env, info }, msg)?)
+    state::init(Context { deps, env, info }, msg)
 }
 
 /// Executes thread operations including creating replies, voting, editing,
 /// deleting, tipping, flagging, and lifecycle management.
 ///
-/// Operations are authorized based on the sender:
+/// Privileged operations are authorized against the role matrix in
+/// state::permissions:
 /// - Reply, Vote, Tip, Save, Flag: Any user
 /// - Edit: Post creator only
-/// - Delete: Post creator or contract owner
-/// - SetConfig: Contract owner only
+/// - Delete: Post creator, owner or moderators
+/// - Delete (hard), Restore: Owner or moderators
+/// - Lock/Unlock: Owner or moderators
+/// - Unhide, ResolveFlags: Owner or moderators
+/// - Ban/Unban: Owner or moderators
+/// - UpdateModerators: Owner only
+/// - TransferOwnership: Owner only
+/// - AcceptOwnership: Pending owner only
+/// - CancelOwnershipTransfer: Owner or pending owner
+/// - SetConfig: Owner only
+/// - ContinueMigration, ContinueDeletion: Any user
 /// - Lifecycle (Setup/Teardown/Suspend/Resume): Table contract only
 #[entry_point]
 pub fn execute(
@@ -70,9 +95,30 @@ pub fn execute(
         ExecuteMsg::Tip(token_amount) => exec_tip(ctx, token_amount),
         ExecuteMsg::Save(ids) => exec_toggle_save(ctx, true, ids),
         ExecuteMsg::Unsave(ids) => exec_toggle_save(ctx, false, ids),
-        ExecuteMsg::Delete { id } => exec_delete_node(ctx, id),
+        ExecuteMsg::Delete { id, hard } => exec_delete_node(ctx, id, hard.unwrap_or(false)),
+        ExecuteMsg::Restore { id } => exec_restore(ctx, id),
+        ExecuteMsg::Lock { id } => exec_lock(ctx, id),
+        ExecuteMsg::Unlock { id } => exec_unlock(ctx, id),
         ExecuteMsg::Flag { id, reason } => exec_flag(ctx, id, reason),
         ExecuteMsg::Unflag { id } => exec_unflag(ctx, id),
+        ExecuteMsg::Unhide { id } => exec_unhide(ctx, id),
+        ExecuteMsg::ResolveFlags { id, action } => exec_resolve_flags(ctx, id, action),
+        ExecuteMsg::Ban {
+            address,
+            expires_at,
+            reason,
+            purge,
+        } => exec_ban(ctx, address, expires_at, reason, purge.unwrap_or(false)),
+        ExecuteMsg::Unban { address } => exec_unban(ctx, address),
+        ExecuteMsg::UpdateModerators { add, remove } => exec_update_moderators(ctx, add, remove),
+        ExecuteMsg::TransferOwnership {
+            new_owner,
+            expires_at,
+        } => exec_transfer_ownership(ctx, new_owner, expires_at),
+        ExecuteMsg::AcceptOwnership {} => exec_accept_ownership(ctx),
+        ExecuteMsg::CancelOwnershipTransfer {} => exec_cancel_ownership_transfer(ctx),
+        ExecuteMsg::ContinueMigration { limit } => exec_continue_migration(ctx, limit),
+        ExecuteMsg::ContinueDeletion { limit } => exec_continue_deletion(ctx, limit),
         ExecuteMsg::Lifecycle(msg) => match msg {
             LifecycleExecuteMsg::Setup(args) => exec_setup(ctx, args),
             LifecycleExecuteMsg::Teardown(args) => exec_teardown(ctx, args),
@@ -83,7 +129,8 @@ pub fn execute(
 }
 
 /// Queries thread data including thread info, nodes by ID, child nodes,
-/// ancestor nodes, and nodes by tag or mention.
+/// ancestor nodes, and nodes by tag or mention, as well as the thread's
+/// configuration, content limits and contract version.
 ///
 /// All queries are read-only and do not modify state. Some queries support
 /// pagination for efficient data retrieval of large result sets.
@@ -96,42 +143,126 @@ pub fn query(
     env: Env,
     msg: QueryMsg,
 ) -> Result<Binary, ContractError> {
+    // Nothing can be read reliably once the thread's state is being purged
+    state::ensure_not_purging(deps.storage)?;
     let ctx = ReadonlyContext { deps, env };
     let result = match msg {
         QueryMsg::Thread { sender } => to_json_binary(&query_thread_info(ctx, sender)?),
+        QueryMsg::Bans { cursor, limit } => to_json_binary(&query_bans(ctx, cursor, limit)?),
+        QueryMsg::Config {} => to_json_binary(&query_config(ctx)?),
+        QueryMsg::Version {} => to_json_binary(&query_version(ctx)?),
+        QueryMsg::Permissions { address, id } => {
+            to_json_binary(&query_permissions(ctx, address, id)?)
+        },
+        QueryMsg::Votes {
+            address,
+            sentiment_filter,
+            cursor,
+            limit,
+        } => to_json_binary(&query_votes(ctx, address, sentiment_filter, cursor, limit)?),
         QueryMsg::Nodes(msg) => match msg {
             NodesQueryMsg::ByIds { ids, sender } => {
                 to_json_binary(&query_nodes_by_id(ctx, ids, sender)?)
             },
             NodesQueryMsg::Children {
                 id,
+                order,
                 cursor,
                 limit,
                 sender,
-            } => to_json_binary(&query_child_nodes(ctx, id, cursor, limit, sender)?),
+            } => to_json_binary(&query_child_nodes(ctx, id, order, cursor, limit, sender)?),
             NodesQueryMsg::WithTag {
                 tag,
+                order,
                 cursor,
+                limit,
                 sender,
             } => to_json_binary(&query_nodes_by_tag_or_mention(
                 ctx,
-                TagWrapper::Tag(tag),
+                Some(TagFilter::AllOf(vec![tag])),
+                None,
+                order,
                 cursor,
+                limit,
                 sender,
             )?),
             NodesQueryMsg::WithMention {
                 mention,
+                order,
                 cursor,
+                limit,
                 sender,
             } => to_json_binary(&query_nodes_by_tag_or_mention(
                 ctx,
-                TagWrapper::Mention(mention),
+                None,
+                Some(mention),
+                order,
                 cursor,
+                limit,
+                sender,
+            )?),
+            NodesQueryMsg::Tagged {
+                tags,
+                mention,
+                order,
+                cursor,
+                limit,
+                sender,
+            } => to_json_binary(&query_nodes_by_tag_or_mention(
+                ctx,
+                Some(tags),
+                mention,
+                order,
+                cursor,
+                limit,
                 sender,
             )?),
             NodesQueryMsg::Ancestors { id, levels, sender } => {
                 to_json_binary(&query_ancestor_nodes(ctx, id, levels, sender)?)
             },
+            NodesQueryMsg::Subtree {
+                id,
+                max_depth,
+                max_nodes,
+                order,
+                cursor,
+                sender,
+            } => to_json_binary(&query_subtree(
+                ctx, id, max_depth, max_nodes, order, cursor, sender,
+            )?),
+            NodesQueryMsg::ByAuthor {
+                address,
+                cursor,
+                limit,
+                sender,
+            } => to_json_binary(&query_nodes_by_author(ctx, address, cursor, limit, sender)?),
+            NodesQueryMsg::Saved {
+                address,
+                cursor,
+                limit,
+            } => to_json_binary(&query_saved_nodes(ctx, address, cursor, limit)?),
+            NodesQueryMsg::Voters {
+                id,
+                sentiment,
+                cursor,
+                limit,
+            } => to_json_binary(&query_voters(ctx, id, sentiment, cursor, limit)?),
+            NodesQueryMsg::Flagged {
+                cursor,
+                limit,
+                sender,
+            } => to_json_binary(&query_flagged_nodes(ctx, cursor, limit, sender)?),
+            NodesQueryMsg::Revisions {
+                id,
+                cursor,
+                limit,
+                sender,
+            } => to_json_binary(&query_revisions(ctx, id, cursor, limit, sender)?),
+            NodesQueryMsg::Deleted {
+                cursor,
+                limit,
+                sender,
+            } => to_json_binary(&query_deleted_nodes(ctx, cursor, limit, sender)?),
         },
     }?;
     Ok(result)
@@ -139,14 +270,15 @@ pub fn query(
 
 /// Migrates the contract to a new version.
 ///
-/// Updates the contract version metadata stored on-chain. This is called
-/// when upgrading the contract code to a new version.
+/// Refuses downgrades and state written by other contracts, applies any
+/// config updates in the message, then runs the data migrations registered
+/// between the stored and new versions. Migrations that don't complete within
+/// the message's batch size are finished through ContinueMigration.
 #[entry_point]
 pub fn migrate(
     deps: DepsMut,
-    _env: Env,
-    _msg: MigrateMsg,
+    env: Env,
+    msg: MigrateMsg,
 ) -> Result<Response, ContractError> {
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::default())
+    migrations::migrate(deps, env, msg)
 }
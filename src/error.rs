@@ -1,6 +1,8 @@
 use cosmwasm_std::StdError;
 use thiserror::Error;
 
+use crate::state::models::ThreadStatus;
+
 #[derive(Debug, Error)]
 pub enum ContractError {
     #[error("{0}")]
@@ -15,14 +17,38 @@ pub enum ContractError {
     #[error("NodeNotFound: node {node_id:?} not found")]
     NodeNotFound { node_id: u32 },
 
+    #[error("NodeLocked: node {node_id:?} is locked")]
+    NodeLocked { node_id: u32 },
+
+    #[error("NodeDeleted: node {node_id:?} has been deleted")]
+    NodeDeleted { node_id: u32 },
+
     #[error("AlreadyVoted: already voted this way for {node_id:?}")]
     AlreadyVoted { node_id: u32 },
 
+    #[error("EditNotAllowed: {reason:?}")]
+    EditNotAllowed { reason: String },
+
     #[error("ValidationError: {reason:?}")]
     ValidationError { reason: String },
 
     #[error("UnauthorizedTipToken: token type not allowed for tips: {token}")]
     UnauthorizedTipToken { token: String },
+
+    #[error("Banned: {address} is banned from this thread")]
+    Banned { address: String },
+
+    #[error("ThreadNotActive: thread is {status:?}")]
+    ThreadNotActive { status: ThreadStatus },
+
+    #[error("ThreadDeleted: thread is being deleted, see ContinueDeletion")]
+    ThreadDeleted {},
+
+    #[error("MigrationPending: state migration in progress, see ContinueMigration")]
+    MigrationPending {},
+
+    #[error("InvalidMigration: {reason:?}")]
+    InvalidMigration { reason: String },
 }
 
 impl From<ContractError> for StdError {
@@ -0,0 +1,118 @@
+use crate::{
+    error::ContractError,
+    state::{
+        ensure_not_terminated,
+        models::{AuthorPurge, BanMetadata, Tombstone},
+        permissions::{authorize, Action},
+        storage::BANS,
+    },
+};
+use cosmwasm_std::{attr, Addr, Response, Timestamp};
+
+use super::{
+    delete_node::{
+        is_deletion_pending, process_deletions, schedule_purge, DEFAULT_DELETION_BATCH_SIZE,
+    },
+    Context,
+};
+
+/// Bans an address from replying, voting, flagging and tipping in the thread,
+/// either indefinitely or until the given expiry. Banning an address that is
+/// already banned replaces the existing ban.
+///
+/// If purge is set, every node authored by the address is deleted along with
+/// its replies. The root node is never purged. At most
+/// DEFAULT_DELETION_BATCH_SIZE of the address's nodes are visited or deleted
+/// here, and as with hard deletes, large purges are finished through
+/// ContinueDeletion. Nodes that haven't been visited yet remain visible until
+/// then.
+///
+/// Authorization: Owner or moderators (see Action::Ban).
+pub fn exec_ban(
+    ctx: Context,
+    address: Addr,
+    expires_at: Option<Timestamp>,
+    reason: Option<String>,
+    purge: bool,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    let role = authorize(deps.as_ref(), &info.sender, Action::Ban, None)?;
+
+    let address = deps.api.addr_validate(address.as_str())?;
+
+    if let Some(expires_at) = expires_at {
+        if expires_at <= env.block.time {
+            return Err(ContractError::ValidationError {
+                reason: "Ban expiry must be in the future".to_owned(),
+            });
+        }
+    }
+
+    BANS.save(
+        deps.storage,
+        &address,
+        &BanMetadata {
+            address: address.clone(),
+            banned_at: env.block.time,
+            banned_by: info.sender.clone(),
+            expires_at,
+            reason,
+        },
+    )?;
+
+    let mut n_purged: u32 = 0;
+
+    if purge {
+        let purge = AuthorPurge {
+            tombstone: Tombstone {
+                deleted_at: env.block.time,
+                deleted_by: info.sender.clone(),
+                role,
+            },
+            cursor: None,
+        };
+        let (n_visited, n_scheduled) =
+            schedule_purge(deps.storage, &address, &purge, DEFAULT_DELETION_BATCH_SIZE)?;
+        n_purged = n_scheduled;
+        if n_visited < DEFAULT_DELETION_BATCH_SIZE {
+            process_deletions(deps.storage, DEFAULT_DELETION_BATCH_SIZE - n_visited)?;
+        }
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "ban"),
+        attr("address", address.to_string()),
+        attr("n_purged", n_purged.to_string()),
+        attr("pending", is_deletion_pending(deps.storage).to_string()),
+    ]))
+}
+
+/// Lifts a ban, whether or not it has expired.
+///
+/// Authorization: Same as exec_ban.
+pub fn exec_unban(
+    ctx: Context,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    authorize(deps.as_ref(), &info.sender, Action::Ban, None)?;
+
+    if !BANS.has(deps.storage, &address) {
+        return Err(ContractError::ValidationError {
+            reason: format!("{} is not banned", address),
+        });
+    }
+
+    BANS.remove(deps.storage, &address);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "unban"),
+        attr("address", address.to_string()),
+    ]))
+}
@@ -1,70 +1,105 @@
 use crate::{
     error::ContractError,
     state::{
-        is_action_authorized,
-        models::{NodeMetadata, TableMetadata, ROOT_ID},
+        ensure_not_terminated,
+        models::{AuthorPurge, NodeMetadata, TableMetadata, Tombstone, ROOT_ID},
+        permissions::{authorize, Action},
         storage::{
-            ACTIVITY_SCORE, IX_CHILD, IX_MENTION_NODE, IX_NODE_MENTION, IX_RANKED_CHILD, IX_TAG_NODE,
-            NODE_ID_2_BODY, NODE_ID_2_FLAG, NODE_ID_2_METADATA, NODE_ID_2_SECTION,
-            NODE_ID_ADDR_2_SENTIMENT, TABLE,
+            ACTIVITY_SCORE, ADDR_NODE_ID_2_SENTIMENT, IX_ADDR_SAVED_AT, IX_AUTHOR_NODE, IX_CHILD,
+            IX_FLAGGED, IX_MENTION_NODE, IX_NODE_MENTION, IX_NODE_TAG, IX_TAG_NODE, NODE_ID_2_BODY,
+            NODE_ID_2_FLAG, NODE_ID_2_LOCK, NODE_ID_2_METADATA, NODE_ID_2_REVISION,
+            NODE_ID_2_SECTION, NODE_ID_2_TITLE, NODE_ID_2_TOMBSTONE, NODE_ID_ADDR_2_SAVED_AT,
+            NODE_ID_ADDR_2_SENTIMENT, PENDING_DELETIONS, PENDING_PURGES, PURGE_PENDING, TABLE,
         },
     },
-    util::load_node_metadata,
+    util::{load_node_metadata, unindex_child, update_mentions, update_tags},
 };
-use cosmwasm_std::{attr, Addr, Order, Response, Storage};
+use cosmwasm_std::{attr, Addr, Order, Response, StdResult, Storage, Timestamp};
+use cw_storage_plus::Bound;
 use cw_table::{client::Table, msg::KeyValue};
 
-use super::Context;
+use super::{moderation::dismiss_flags, recycle_bin::recycle_node, Context};
 
-/// Deletes a node and all its descendants from the thread.
+/// Max number of nodes or storage keys removed by a hard delete before the
+/// rest is deferred to ContinueDeletion.
+pub const DEFAULT_DELETION_BATCH_SIZE: u32 = 100;
+
+/// Deletes a node.
+///
+/// By default, the node is soft deleted: its title, body, sections, tags,
+/// mentions and edit history are removed and it is marked with a tombstone
+/// recording who deleted it, while its replies remain visible and navigable.
+/// Soft deleted nodes can no longer be replied to, voted on, flagged or
+/// edited.
 ///
+/// A hard delete removes the node and all its descendants from the thread.
 /// If deleting the root node (id == ROOT_ID), purges all contract state and
 /// removes the thread from its parent table contract.
 ///
 /// For non-root nodes:
-/// - Recursively collects all descendant node IDs using depth-first traversal
-/// - Deletes descendants in reverse order (bottom-up) to maintain referential integrity
-/// - Updates parent's reply count
+/// - Detaches the node from its parent and updates the parent's reply count
+/// - Deletes descendants bottom-up to maintain referential integrity
 /// - Updates activity score in table contract if applicable
 ///
-/// Authorization: Only the node creator or contract owner can delete a node.
+/// Hard deletes process at most DEFAULT_DELETION_BATCH_SIZE nodes (or storage
+/// keys, when deleting the root) before deferring the rest to
+/// ContinueDeletion. Queries treat the whole subtree as deleted immediately.
+///
+/// Authorization: Node creator, owner or moderators (see Action::Delete). Hard
+/// deletes are limited to the owner and moderators (see Action::HardDelete).
 pub fn exec_delete_node(
     ctx: Context,
     id: u32,
+    hard: bool,
 ) -> Result<Response, ContractError> {
     let Context { deps, env, info } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
     let node = load_node_metadata(deps.storage, id, true)?.unwrap();
     let maybe_table_metadata = TABLE.may_load(deps.storage)?;
-    let mut resp = Response::new().add_attributes(vec![attr("action", "delete")]);
+    let mut resp = Response::new().add_attributes(vec![
+        attr("action", "delete"),
+        attr("node_id", id.to_string()),
+        attr("hard", hard.to_string()),
+    ]);
 
-    if !(node.created_by == info.sender
-        || is_action_authorized(&deps, &info.sender, "/thread/delete")?)
-    {
-        return Err(ContractError::NotAuthorized {
-            reason: "Not authorized to delete the thread".to_owned(),
-        });
+    if !hard {
+        let role = authorize(
+            deps.as_ref(),
+            &info.sender,
+            Action::Delete,
+            Some(&node.created_by),
+        )?;
+        let tombstone = Tombstone {
+            deleted_at: env.block.time,
+            deleted_by: info.sender.clone(),
+            role,
+        };
+        soft_delete_node(deps.storage, node, &tombstone)?;
+        return Ok(resp);
     }
 
-    if id == ROOT_ID {
-        // Zero-out all contract storage
-        purge_contract_state(deps.storage);
+    let role = authorize(deps.as_ref(), &info.sender, Action::HardDelete, None)?;
+
+    let n_deleted = if id == ROOT_ID {
+        // Zero-out all contract storage, finishing through ContinueDeletion
+        PURGE_PENDING.save(deps.storage, &true)?;
+        let n_deleted = purge_contract_state(deps.storage, DEFAULT_DELETION_BATCH_SIZE);
         // Remove from table
         if let Some(TableMetadata { address, .. }) = maybe_table_metadata {
             let table = Table::new(&address, &env.contract.address);
             resp = resp.add_message(table.delete()?);
         }
+        n_deleted
     } else {
-        // Collect all descendant node IDs recursively
-        let child_ids = collect_all_descendants(deps.storage, id)?;
-
-        // Delete all descendants (bottom-up to avoid parent reference issues)
-        for child_id in child_ids.iter().rev() {
-            let child_node = load_node_metadata(deps.storage, *child_id, true)?.unwrap();
-            delete_child_node(deps.storage, &child_node)?;
-        }
-
-        // Delete the node itself
-        delete_child_node(deps.storage, &node)?;
+        let tombstone = Tombstone {
+            deleted_at: env.block.time,
+            deleted_by: info.sender.clone(),
+            role,
+        };
+        schedule_deletion(deps.storage, &node, &tombstone)?;
+        let n_deleted = process_deletions(deps.storage, DEFAULT_DELETION_BATCH_SIZE)?;
 
         // Update table if applicable
         if let Some(TableMetadata { address, .. }) = maybe_table_metadata {
@@ -81,71 +116,276 @@ pub fn exec_delete_node(
                 None,
             )?);
         }
+        n_deleted
+    };
+
+    Ok(resp.add_attributes(vec![
+        attr("n_deleted", n_deleted.to_string()),
+        attr("pending", is_deletion_pending(deps.storage).to_string()),
+    ]))
+}
+
+/// Moves a node's content to the recycle bin and marks the node with a
+/// tombstone, leaving its place in the tree, its votes and its replies intact.
+fn soft_delete_node(
+    store: &mut dyn Storage,
+    mut node: NodeMetadata,
+    tombstone: &Tombstone,
+) -> Result<(), ContractError> {
+    let id = node.id;
+
+    if NODE_ID_2_TOMBSTONE.has(store, id) {
+        return Err(ContractError::NodeDeleted { node_id: id });
+    }
+
+    recycle_node(store, &node, tombstone)?;
+
+    NODE_ID_2_TITLE.remove(store, id);
+    NODE_ID_2_BODY.save(store, id, &String::new())?;
+    for i in 0..node.n_sections {
+        NODE_ID_2_SECTION.remove(store, (id, i));
     }
+    node.n_sections = 0;
 
-    Ok(resp)
+    update_tags(store, id, None, true)?;
+    update_mentions(store, id, None, true)?;
+    remove_revisions(store, id);
+
+    // There is nothing left to moderate
+    dismiss_flags(store, &mut node)?;
+    node.hidden_at = None;
+
+    NODE_ID_2_METADATA.save(store, id, &node)?;
+    NODE_ID_2_TOMBSTONE.save(store, id, tombstone)?;
+
+    Ok(())
 }
 
-/// Recursively collects all descendant node IDs using depth-first traversal.
-///
-/// Uses an iterative approach with a stack to avoid stack overflow on deep trees.
-/// Returns all descendant IDs in order of discovery (not deletion order).
-/// Caller should reverse the list for bottom-up deletion.
-fn collect_all_descendants(
-    store: &dyn Storage,
-    parent_id: u32,
-) -> Result<Vec<u32>, ContractError> {
-    let mut all_descendants = Vec::new();
-    let mut to_visit = vec![parent_id];
-
-    while let Some(current_id) = to_visit.pop() {
-        // Get direct children of current node
-        let children: Vec<u32> = IX_CHILD
-            .prefix(current_id)
-            .keys(store, None, None, Order::Ascending)
-            .map(|r| r.unwrap())
-            .collect();
+/// Schedules the deletion of a non-root node along with all of its
+/// descendants. The node is detached from its parent right away, and the
+/// subtree is treated as deleted from then on, but its nodes are only moved to
+/// the recycle bin by process_deletions.
+pub fn schedule_deletion(
+    store: &mut dyn Storage,
+    node: &NodeMetadata,
+    tombstone: &Tombstone,
+) -> Result<(), ContractError> {
+    detach_node(store, node)?;
+    PENDING_DELETIONS.save(store, node.id, tombstone)?;
+    Ok(())
+}
+
+/// Schedules the deletion of nodes authored by `address`, visiting at most
+/// `limit` of them from the purge's cursor on. If any remain, the purge is
+/// saved so that process_deletions can resume it. Returns the number of nodes
+/// visited and the number scheduled.
+pub fn schedule_purge(
+    store: &mut dyn Storage,
+    address: &Addr,
+    purge: &AuthorPurge,
+    limit: u32,
+) -> Result<(u32, u32), ContractError> {
+    let node_ids = IX_AUTHOR_NODE
+        .prefix(address)
+        .keys(
+            store,
+            purge.cursor.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .collect::<StdResult<Vec<u32>>>()?;
 
-        // Add children to descendants list
-        all_descendants.extend(children.iter());
+    let mut n_scheduled: u32 = 0;
 
-        // Add children to visit queue for recursive traversal
-        to_visit.extend(children);
+    for node_id in node_ids.iter().filter(|id| **id != ROOT_ID) {
+        // Nodes may already be gone if they replied to one another
+        if let Some(node) = load_node_metadata(store, *node_id, false)? {
+            schedule_deletion(store, &node, &purge.tombstone)?;
+            n_scheduled += 1;
+        }
+    }
+
+    if node_ids.len() < limit as usize {
+        PENDING_PURGES.remove(store, address);
+    } else {
+        PENDING_PURGES.save(
+            store,
+            address,
+            &AuthorPurge {
+                tombstone: purge.tombstone.clone(),
+                cursor: node_ids.last().copied(),
+            },
+        )?;
     }
 
-    Ok(all_descendants)
+    Ok((node_ids.len() as u32, n_scheduled))
 }
 
-/// Purges all contract state by iterating through storage keys in batches.
-///
-/// Processes 20 keys at a time to avoid running out of gas on large datasets.
-/// Used when deleting the root node to completely reset the contract.
-fn purge_contract_state(storage: &mut dyn Storage) {
-    loop {
-        let keys: Vec<_> = storage
-            .range_keys(None, None, Order::Ascending)
-            .take(20)
-            .collect();
-        if keys.is_empty() {
-            break;
+/// Returns true if a scheduled deletion, a ban purge or a purge of the
+/// thread's state has yet to complete.
+pub fn is_deletion_pending(store: &dyn Storage) -> bool {
+    PURGE_PENDING.exists(store)
+        || PENDING_DELETIONS
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .is_some()
+        || PENDING_PURGES
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .is_some()
+}
+
+/// Processes up to `limit` nodes, or storage keys if the thread's state is
+/// being purged. Unfinished ban purges are resumed first, each node visited
+/// counting towards the limit, then nodes of scheduled subtrees are removed
+/// from storage. Each subtree is deleted from the leaves up, so that
+/// interrupted deletions never leave orphaned nodes. Returns the number of
+/// nodes or keys processed.
+pub fn process_deletions(
+    store: &mut dyn Storage,
+    limit: u32,
+) -> Result<u32, ContractError> {
+    if PURGE_PENDING.exists(store) {
+        return Ok(purge_contract_state(store, limit));
+    }
+
+    let limit = limit.max(1);
+    let mut n_deleted: u32 = 0;
+
+    while n_deleted < limit {
+        let (address, purge) = match PENDING_PURGES
+            .range(store, None, None, Order::Ascending)
+            .next()
+        {
+            Some(result) => result?,
+            None => break,
+        };
+        let (n_visited, _) = schedule_purge(store, &address, &purge, limit - n_deleted)?;
+        n_deleted += n_visited;
+    }
+
+    while n_deleted < limit {
+        let (root_id, tombstone) = match PENDING_DELETIONS
+            .range(store, None, None, Order::Ascending)
+            .next()
+        {
+            Some(result) => result?,
+            None => break,
+        };
+
+        // Descend to any leaf of the subtree
+        let mut id = root_id;
+        while let Some(result) = IX_CHILD
+            .prefix(id)
+            .keys(store, None, None, Order::Ascending)
+            .next()
+        {
+            id = result?;
         }
-        for k in keys {
-            storage.remove(&k);
+
+        let node = NODE_ID_2_METADATA.load(store, id)?;
+        if id == root_id {
+            // Already detached when the deletion was scheduled
+            PENDING_DELETIONS.remove(store, id);
+        } else {
+            detach_node(store, &node)?;
         }
+        recycle_node(store, &node, &tombstone)?;
+        delete_child_node(store, &node)?;
+        n_deleted += 1;
+    }
+
+    Ok(n_deleted)
+}
+
+/// Continues deletions that didn't finish within the Delete, Ban or
+/// ResolveFlags call that started them, removing at most `limit` nodes, or
+/// storage keys if the root node was deleted.
+///
+/// Authorization: Anyone. Deletions are deterministic, so callers can only
+/// affect how quickly they complete.
+pub fn exec_continue_deletion(
+    ctx: Context,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+
+    if !is_deletion_pending(deps.storage) {
+        return Err(ContractError::ValidationError {
+            reason: "No deletion in progress".to_owned(),
+        });
+    }
+
+    let n_deleted = process_deletions(deps.storage, limit.unwrap_or(DEFAULT_DELETION_BATCH_SIZE))?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "continue_deletion"),
+        attr("n_deleted", n_deleted.to_string()),
+        attr("pending", is_deletion_pending(deps.storage).to_string()),
+    ]))
+}
+
+/// Purges up to `limit` keys of contract state, marking the purge as complete
+/// once no keys remain. Used when deleting the root node to completely reset
+/// the contract. Returns the number of keys removed.
+fn purge_contract_state(
+    storage: &mut dyn Storage,
+    limit: u32,
+) -> u32 {
+    let keys: Vec<_> = storage
+        .range_keys(None, None, Order::Ascending)
+        .filter(|k| k.as_slice() != PURGE_PENDING.as_slice())
+        .take(limit.max(1) as usize)
+        .collect();
+    for k in keys.iter() {
+        storage.remove(k);
+    }
+    if keys.len() < limit.max(1) as usize {
+        PURGE_PENDING.remove(storage);
+    }
+    keys.len() as u32
+}
+
+/// Removes a node from its parent's children and updates the parent's reply
+/// count.
+fn detach_node(
+    store: &mut dyn Storage,
+    node: &NodeMetadata,
+) -> Result<(), ContractError> {
+    if let Some(parent_id) = node.parent_id {
+        // Remove child relationship and reply orderings
+        unindex_child(store, node);
+        // Update parent metadata
+        NODE_ID_2_METADATA.update(
+            store,
+            parent_id,
+            |maybe_parent| -> Result<_, ContractError> {
+                if let Some(mut parent) = maybe_parent {
+                    parent.n_replies -= 1;
+                    Ok(parent)
+                } else {
+                    Err(ContractError::NodeNotFound { node_id: parent_id })
+                }
+            },
+        )?;
     }
+    Ok(())
 }
 
 /// Deletes a single node and all its associated data from storage.
 ///
 /// Removes:
 /// - Node metadata and body
-/// - Parent-child relationships (IX_CHILD, IX_RANKED_CHILD)
+/// - Author index entry
 /// - Sentiment/voting data
-/// - Flags
+/// - Saves, from each saver's saved nodes
+/// - Flags and moderation queue entry
+/// - Lock
 /// - Sections
 /// - Tags and mentions (both forward and reverse indices)
 ///
-/// Also updates the parent's reply count if the node has a parent.
+/// The node must already be detached from its parent (see detach_node).
 /// Uses node-scoped index queries for efficient O(M) deletion where M is
 /// the data size for this node, rather than O(N) over all nodes.
 fn delete_child_node(
@@ -157,25 +397,8 @@ fn delete_child_node(
     // Remove metadata
     NODE_ID_2_METADATA.remove(store, id);
 
-    if let Some(parent_id) = node.parent_id {
-        // Remove child relationship
-        IX_CHILD.remove(store, (parent_id, id));
-        // Remove from ranked child ordering map
-        IX_RANKED_CHILD.remove(store, (parent_id, node.rank, id));
-        // Update parent metadata
-        NODE_ID_2_METADATA.update(
-            store,
-            parent_id,
-            |maybe_parent| -> Result<_, ContractError> {
-                if let Some(mut parent) = maybe_parent {
-                    parent.n_replies -= 1;
-                    Ok(parent)
-                } else {
-                    Err(ContractError::NodeNotFound { node_id: parent_id })
-                }
-            },
-        )?;
-    }
+    // Remove from author's nodes
+    IX_AUTHOR_NODE.remove(store, (&node.created_by, id));
 
     // Purge the node's sentiment state
     {
@@ -186,13 +409,31 @@ fn delete_child_node(
             .collect();
         for addr in addrs.iter() {
             NODE_ID_ADDR_2_SENTIMENT.remove(store, (id, addr));
+            ADDR_NODE_ID_2_SENTIMENT.remove(store, (addr, id));
+        }
+    }
+
+    // Remove the node from saved nodes
+    {
+        let saves: Vec<(Addr, Timestamp)> = NODE_ID_ADDR_2_SAVED_AT
+            .prefix(id)
+            .range(store, None, None, Order::Ascending)
+            .map(|r| r.unwrap())
+            .collect();
+        for (addr, saved_at) in saves.iter() {
+            NODE_ID_ADDR_2_SAVED_AT.remove(store, (id, addr));
+            IX_ADDR_SAVED_AT.remove(store, (addr, saved_at.seconds(), id));
         }
     }
 
     // Remove node body text
     NODE_ID_2_BODY.remove(store, id);
 
+    // Remove lock, if any
+    NODE_ID_2_LOCK.remove(store, id);
+
     // Remove flagged addresses with respect to the node
+    IX_FLAGGED.remove(store, (node.n_flags, id));
     {
         let addrs: Vec<Addr> = NODE_ID_2_FLAG
             .prefix(id)
@@ -209,6 +450,10 @@ fn delete_child_node(
         NODE_ID_2_SECTION.remove(store, (id, i));
     }
 
+    // Remove edit history and tombstone, if any
+    remove_revisions(store, id);
+    NODE_ID_2_TOMBSTONE.remove(store, id);
+
     // Remove tags (node-specific)
     {
         let tags: Vec<String> = IX_NODE_TAG
@@ -236,3 +481,18 @@ fn delete_child_node(
     }
     Ok(())
 }
+
+/// Removes a node's edit history.
+fn remove_revisions(
+    store: &mut dyn Storage,
+    id: u32,
+) {
+    let revisions: Vec<u16> = NODE_ID_2_REVISION
+        .prefix(id)
+        .keys(store, None, None, Order::Ascending)
+        .map(|r| r.unwrap())
+        .collect();
+    for revision in revisions {
+        NODE_ID_2_REVISION.remove(store, (id, revision));
+    }
+}
@@ -1,68 +1,230 @@
 use crate::{
     error::ContractError,
-    msg::NodeEditMsg,
-    state::storage::{NODE_ID_2_BODY, NODE_ID_2_METADATA, NODE_ID_2_SECTION, NODE_ID_2_TITLE},
-    util::{load_node_metadata, process_tags_and_mentions},
-    validation::{validate_body, validate_mentions, validate_sections, validate_tags, validate_title},
+    msg::{NodeEditField, NodeEditMsg},
+    state::{
+        ensure_active,
+        models::{Limits, NodeMetadata, Revision},
+        permissions::{authorize, find_authorizing_role, Action},
+        storage::{
+            CONFIG_EDIT_POLICY, NODE_ID_2_BODY, NODE_ID_2_MENTIONS, NODE_ID_2_METADATA,
+            NODE_ID_2_REVISION, NODE_ID_2_SECTION, NODE_ID_2_TAGS, NODE_ID_2_TITLE,
+        },
+    },
+    util::{ensure_not_deleted, load_node_metadata, update_mentions, update_tags},
+    validation::{
+        validate_body, validate_mentions, validate_sections, validate_tags, validate_title,
+    },
 };
-use cosmwasm_std::{attr, Order, Response};
+use cosmwasm_std::{attr, Addr, Order, Response, Storage, Timestamp};
+use cw_storage_plus::Bound;
 
 use super::Context;
 
+/// Edits a node. Only the fields given in the message are updated, so a post
+/// can be retagged or retitled without resending its body. The replaced
+/// content is kept as a revision.
+///
+/// Authors may be restricted by the thread's edit policy to editing within a
+/// window after creation, or before the post is replied to or voted on.
+///
+/// Authorization: Node creator (see Action::Edit), or the owner and moderators
+/// if the edit policy allows moderators to edit.
 pub fn exec_edit_node(
     ctx: Context,
     msg: NodeEditMsg,
 ) -> Result<Response, ContractError> {
     let Context { deps, env, info } = ctx;
-    let mut metadata = load_node_metadata(deps.storage, msg.id, true)?.unwrap();
+    let NodeEditMsg {
+        id,
+        title,
+        body,
+        sections,
+        tags,
+        mentions,
+        clear,
+    } = msg;
+    let clear = clear.unwrap_or_default();
 
-    // Only the post creator can edit it
-    if metadata.created_by != info.sender {
-        return Err(ContractError::NotAuthorized {
-            reason: "Only the post creator can edit it".to_owned(),
-        });
-    }
+    ensure_active(deps.storage)?;
 
-    metadata.updated_at = Some(env.block.time);
-    NODE_ID_2_METADATA.save(deps.storage, metadata.id, &metadata)?;
-
-    if let Some(new_body) = &msg.body {
-        validate_body(new_body)?;
-        validate_tags(&msg.tags)?;
-        validate_mentions(&msg.mentions)?;
-        process_tags_and_mentions(deps.storage, msg.id, msg.tags, msg.mentions, true)?;
-        NODE_ID_2_BODY.save(deps.storage, msg.id, new_body)?;
-        if msg.title.is_some() {
-            if metadata.parent_id.is_some() {
-                return Err(ContractError::ValidationError {
-                    reason: "Only the root node has a title".to_owned(),
+    let mut metadata = load_node_metadata(deps.storage, id, true)?.unwrap();
+
+    ensure_not_deleted(deps.storage, id)?;
+
+    // Only the post creator can edit it, unless the edit policy also allows
+    // moderators to do so. Restrictions on timing only apply to the creator.
+    let policy = CONFIG_EDIT_POLICY.may_load(deps.storage)?;
+    let author = Some(&metadata.created_by);
+    match find_authorizing_role(deps.as_ref(), &info.sender, Action::Edit, author)? {
+        Some(_) => {
+            if let Some(reason) = policy
+                .as_ref()
+                .and_then(|p| p.check_author_edit(&metadata, env.block.time))
+            {
+                return Err(ContractError::EditNotAllowed { reason });
+            }
+        },
+        None => {
+            if !policy.map(|p| p.moderators_can_edit).unwrap_or(false) {
+                return Err(ContractError::NotAuthorized {
+                    reason: format!("Not authorized to perform {}", Action::Edit.path()),
                 });
-            } else {
-                let title = msg.title.unwrap();
-                validate_title(&title)?;
-                NODE_ID_2_TITLE.save(deps.storage, msg.id, &title)?;
             }
+            authorize(deps.as_ref(), &info.sender, Action::Moderate, None)?;
+        },
+    }
+
+    // Validate all input before changing anything
+    for field in clear.iter() {
+        let is_set = match field {
+            NodeEditField::Title => title.is_some(),
+            NodeEditField::Sections => sections.is_some(),
+            NodeEditField::Tags => tags.is_some(),
+            NodeEditField::Mentions => mentions.is_some(),
+        };
+        if is_set {
+            return Err(ContractError::ValidationError {
+                reason: format!("Cannot both set and clear {:?}", field),
+            });
         }
     }
 
-    if let Some(new_section) = &msg.sections {
-        validate_sections(&msg.sections)?;
+    if body.is_none()
+        && title.is_none()
+        && sections.is_none()
+        && tags.is_none()
+        && mentions.is_none()
+        && clear.is_empty()
+    {
+        return Err(ContractError::ValidationError {
+            reason: "Nothing to edit".to_owned(),
+        });
+    }
+
+    if (title.is_some() || clear.contains(&NodeEditField::Title)) && metadata.parent_id.is_some() {
+        return Err(ContractError::ValidationError {
+            reason: "Only the root node has a title".to_owned(),
+        });
+    }
+
+    let limits = Limits::load(deps.storage)?;
+
+    if let Some(title) = &title {
+        validate_title(title, &limits)?;
+    }
+    if let Some(body) = &body {
+        validate_body(body, &limits)?;
+    }
+    validate_sections(&sections, &limits)?;
+    validate_tags(&tags, &limits)?;
+    validate_mentions(&mentions, &limits)?;
+
+    // Keep a copy of the content being replaced
+    save_revision(
+        deps.storage,
+        &mut metadata,
+        &info.sender,
+        env.block.time,
+        limits.max_revisions,
+    )?;
+
+    if let Some(title) = &title {
+        NODE_ID_2_TITLE.save(deps.storage, id, title)?;
+    } else if clear.contains(&NodeEditField::Title) {
+        NODE_ID_2_TITLE.remove(deps.storage, id);
+    }
+
+    if let Some(body) = &body {
+        NODE_ID_2_BODY.save(deps.storage, id, body)?;
+    }
+
+    if sections.is_some() || clear.contains(&NodeEditField::Sections) {
         // Remove old attachements
         for i in NODE_ID_2_SECTION
-            .prefix(msg.id)
+            .prefix(id)
             .keys(deps.storage, None, None, Order::Ascending)
             .map(|r| r.unwrap())
             .collect::<Vec<u8>>()
         {
-            NODE_ID_2_SECTION.remove(deps.storage, (msg.id, i as u8));
+            NODE_ID_2_SECTION.remove(deps.storage, (id, i));
         }
         // Save new attachements
-        for (i, section) in new_section.iter().enumerate() {
-            NODE_ID_2_SECTION.save(deps.storage, (msg.id, i as u8), section)?;
+        let sections = sections.unwrap_or_default();
+        for (i, section) in sections.iter().enumerate() {
+            NODE_ID_2_SECTION.save(deps.storage, (id, i as u8), section)?;
         }
+        metadata.n_sections = sections.len() as u8;
+    }
+
+    if tags.is_some() || clear.contains(&NodeEditField::Tags) {
+        update_tags(deps.storage, id, tags, true)?;
     }
 
+    if mentions.is_some() || clear.contains(&NodeEditField::Mentions) {
+        update_mentions(deps.storage, id, mentions, true)?;
+    }
+
+    metadata.updated_at = Some(env.block.time);
+    NODE_ID_2_METADATA.save(deps.storage, id, &metadata)?;
+
     // TODO: Prepare data for updating the thread's table if applicable
 
     Ok(Response::new().add_attributes(vec![attr("action", "edit")]))
 }
+
+/// Appends the node's current content to its edit history and prunes the
+/// oldest revisions beyond max_revisions. The caller is responsible for
+/// saving the updated metadata.
+fn save_revision(
+    store: &mut dyn Storage,
+    metadata: &mut NodeMetadata,
+    editor: &Addr,
+    time: Timestamp,
+    max_revisions: u32,
+) -> Result<(), ContractError> {
+    let id = metadata.id;
+
+    if metadata.n_revisions == u16::MAX {
+        return Err(ContractError::ValidationError {
+            reason: format!("Node {} cannot be edited any further", id),
+        });
+    }
+    metadata.n_revisions += 1;
+
+    if max_revisions > 0 {
+        let revision = Revision {
+            revision: metadata.n_revisions,
+            title: NODE_ID_2_TITLE.may_load(store, id)?,
+            body: NODE_ID_2_BODY.load(store, id)?,
+            sections: NODE_ID_2_SECTION
+                .prefix(id)
+                .range(store, None, None, Order::Ascending)
+                .map(|r| r.map(|(_, section)| section))
+                .collect::<Result<Vec<_>, _>>()?,
+            tags: NODE_ID_2_TAGS.may_load(store, id)?.unwrap_or_default(),
+            mentions: NODE_ID_2_MENTIONS.may_load(store, id)?.unwrap_or_default(),
+            edited_by: editor.clone(),
+            edited_at: time,
+        };
+        NODE_ID_2_REVISION.save(store, (id, revision.revision), &revision)?;
+    }
+
+    // Revisions numbered at or below the cutoff are no longer retained
+    let cutoff = (metadata.n_revisions as u32).saturating_sub(max_revisions);
+    if cutoff > 0 {
+        let stale: Vec<u16> = NODE_ID_2_REVISION
+            .prefix(id)
+            .keys(
+                store,
+                None,
+                Some(Bound::inclusive(cutoff as u16)),
+                Order::Ascending,
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        for revision in stale {
+            NODE_ID_2_REVISION.remove(store, (id, revision));
+        }
+    }
+
+    Ok(())
+}
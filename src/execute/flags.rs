@@ -1,26 +1,42 @@
 use crate::{
     error::ContractError,
     state::{
+        ensure_active, ensure_not_banned,
         models::FlagMetadata,
-        storage::{NODE_ID_2_FLAG, NODE_ID_2_METADATA},
+        storage::{CONFIG_FLAG_THRESHOLD, IX_FLAGGED, NODE_ID_2_FLAG, NODE_ID_2_METADATA},
     },
+    util::ensure_not_deleted,
 };
 use cosmwasm_std::{attr, Response};
 
 use super::Context;
 
+/// Flags a node for moderator review, optionally with a reason.
+///
+/// If a flag threshold is configured and the node's flags now exceed it, the
+/// node is hidden pending review: queries redact its content for everyone but
+/// its author and moderators until a moderator unhides or deletes it.
 pub fn exec_flag(
     ctx: Context,
     node_id: u32,
     maybe_reason: Option<String>,
 ) -> Result<Response, ContractError> {
     let Context { deps, info, env } = ctx;
-    NODE_ID_2_METADATA.update(
+    ensure_active(deps.storage)?;
+    ensure_not_banned(deps.storage, &info.sender, env.block.time)?;
+    ensure_not_deleted(deps.storage, node_id)?;
+    let maybe_threshold = CONFIG_FLAG_THRESHOLD.may_load(deps.storage)?;
+    let metadata = NODE_ID_2_METADATA.update(
         deps.storage,
         node_id,
         |maybe_metadata| -> Result<_, ContractError> {
             if let Some(mut metadata) = maybe_metadata {
                 metadata.n_flags += 1;
+                if let Some(threshold) = &maybe_threshold {
+                    if metadata.hidden_at.is_none() && threshold.is_exceeded(&metadata) {
+                        metadata.hidden_at = Some(env.block.time);
+                    }
+                }
                 Ok(metadata)
             } else {
                 Err(ContractError::NodeNotFound { node_id })
@@ -44,9 +60,16 @@ pub fn exec_flag(
         },
     )?;
 
+    // Move the node up the moderation queue
+    IX_FLAGGED.remove(deps.storage, (metadata.n_flags - 1, node_id));
+    IX_FLAGGED.save(deps.storage, (metadata.n_flags, node_id), &true)?;
+
     // TODO: Prepare data for updating the thread's table if applicable
 
-    Ok(Response::new().add_attributes(vec![attr("action", "flag")]))
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "flag"),
+        attr("hidden", metadata.hidden_at.is_some().to_string()),
+    ]))
 }
 
 pub fn exec_unflag(
@@ -54,6 +77,7 @@ pub fn exec_unflag(
     node_id: u32,
 ) -> Result<Response, ContractError> {
     let Context { deps, info, .. } = ctx;
+    ensure_active(deps.storage)?;
     let key = (node_id, &info.sender);
     if !NODE_ID_2_FLAG.has(deps.storage, key) {
         return Err(ContractError::NotAuthorized {
@@ -61,7 +85,7 @@ pub fn exec_unflag(
         });
     }
     NODE_ID_2_FLAG.remove(deps.storage, key);
-    NODE_ID_2_METADATA.update(
+    let metadata = NODE_ID_2_METADATA.update(
         deps.storage,
         node_id,
         |maybe_metadata| -> Result<_, ContractError> {
@@ -73,5 +97,12 @@ pub fn exec_unflag(
             }
         },
     )?;
+
+    // Move the node down the moderation queue, removing it once unflagged
+    IX_FLAGGED.remove(deps.storage, (metadata.n_flags + 1, node_id));
+    if metadata.n_flags > 0 {
+        IX_FLAGGED.save(deps.storage, (metadata.n_flags, node_id), &true)?;
+    }
+
     Ok(Response::new().add_attributes(vec![attr("action", "unflag")]))
 }
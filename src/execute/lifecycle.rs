@@ -1,8 +1,8 @@
 use crate::{
     error::ContractError,
     state::{
-        models::{TableMetadata, ROOT_ID},
-        storage::{NODE_ID_2_METADATA, TABLE},
+        models::{TableMetadata, ThreadStatus, ROOT_ID},
+        storage::{IX_AUTHOR_NODE, NODE_ID_2_METADATA, STATUS, TABLE},
         views::{load_mentions, load_tags},
     },
     util::load_node_metadata,
@@ -29,8 +29,10 @@ pub fn exec_setup(
     let resp = Response::new().add_attributes(vec![attr("action", "setup")]);
     let mut meta = load_node_metadata(deps.storage, ROOT_ID, true)?.unwrap();
 
+    IX_AUTHOR_NODE.remove(deps.storage, (&meta.created_by, meta.id));
     meta.created_by = args.initiator;
     NODE_ID_2_METADATA.save(deps.storage, meta.id, &meta)?;
+    IX_AUTHOR_NODE.save(deps.storage, (&meta.created_by, meta.id), &true)?;
 
     save_table_info(deps.storage, &info.sender, &args.id)?;
 
@@ -64,7 +66,7 @@ pub fn exec_setup(
 fn save_table_info(
     store: &mut dyn Storage,
     table_addr: &Addr,
-    contract_id: &String,
+    contract_id: &str,
 ) -> Result<(), ContractError> {
     if TABLE.exists(store) {
         return Err(ContractError::NotAuthorized {
@@ -76,7 +78,7 @@ fn save_table_info(
         store,
         &TableMetadata {
             address: table_addr.clone(),
-            id: contract_id.clone(),
+            id: contract_id.to_owned(),
         },
     )?;
 
@@ -125,6 +127,9 @@ pub fn exec_teardown(
         });
     }
 
+    // Teardown is terminal: the thread remains queryable but read-only
+    STATUS.save(deps.storage, &ThreadStatus::Terminated)?;
+
     Ok(Response::new().add_attributes(vec![attr("action", "teardown")]))
 }
 
@@ -142,6 +147,8 @@ pub fn exec_suspend(
         });
     }
 
+    transition_status(deps.storage, ThreadStatus::Active, ThreadStatus::Suspended)?;
+
     Ok(Response::new().add_attributes(vec![attr("action", "suspend")]))
 }
 
@@ -159,5 +166,24 @@ pub fn exec_resume(
         });
     }
 
+    transition_status(deps.storage, ThreadStatus::Suspended, ThreadStatus::Active)?;
+
     Ok(Response::new().add_attributes(vec![attr("action", "resume")]))
 }
+
+/// Moves the thread from one status to another, failing if the thread isn't
+/// currently in the expected status.
+fn transition_status(
+    store: &mut dyn Storage,
+    from: ThreadStatus,
+    to: ThreadStatus,
+) -> Result<(), ContractError> {
+    let status = STATUS.load(store)?;
+    if status != from {
+        return Err(ContractError::ValidationError {
+            reason: format!("Cannot move thread from {:?} to {:?}", status, to),
+        });
+    }
+    STATUS.save(store, &to)?;
+    Ok(())
+}
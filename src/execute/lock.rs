@@ -0,0 +1,75 @@
+use crate::{
+    error::ContractError,
+    state::{
+        ensure_not_terminated,
+        models::LockMetadata,
+        permissions::{authorize, Action},
+        storage::NODE_ID_2_LOCK,
+    },
+    util::load_node_metadata,
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+/// Locks a node, closing it and its entire subtree to new replies and votes.
+/// Locking the root node locks the whole thread. Locked nodes remain readable.
+///
+/// Authorization: Owner or moderators (see Action::Lock).
+pub fn exec_lock(
+    ctx: Context,
+    id: u32,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    authorize(deps.as_ref(), &info.sender, Action::Lock, None)?;
+
+    load_node_metadata(deps.storage, id, true)?;
+
+    NODE_ID_2_LOCK.update(deps.storage, id, |maybe_lock| -> Result<_, ContractError> {
+        if maybe_lock.is_some() {
+            return Err(ContractError::ValidationError {
+                reason: format!("Node {} is already locked", id),
+            });
+        }
+        Ok(LockMetadata {
+            locked_at: env.block.time,
+            locked_by: info.sender.clone(),
+        })
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "lock"),
+        attr("node_id", id.to_string()),
+    ]))
+}
+
+/// Removes a lock previously placed on a node. Locks placed on ancestors of
+/// the node are unaffected.
+///
+/// Authorization: Same as exec_lock.
+pub fn exec_unlock(
+    ctx: Context,
+    id: u32,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    authorize(deps.as_ref(), &info.sender, Action::Lock, None)?;
+
+    if !NODE_ID_2_LOCK.has(deps.storage, id) {
+        return Err(ContractError::ValidationError {
+            reason: format!("Node {} is not locked", id),
+        });
+    }
+
+    NODE_ID_2_LOCK.remove(deps.storage, id);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "unlock"),
+        attr("node_id", id.to_string()),
+    ]))
+}
@@ -0,0 +1,17 @@
+use crate::{error::ContractError, migrations::resume};
+use cosmwasm_std::Response;
+
+use super::Context;
+
+/// Continues a state migration that didn't finish within the migrate call,
+/// processing at most `limit` records.
+///
+/// Authorization: Anyone. Migration steps are deterministic, so callers can
+/// only affect how quickly the migration completes.
+pub fn exec_continue_migration(
+    ctx: Context,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, .. } = ctx;
+    resume(deps.storage, &env, limit)
+}
@@ -1,7 +1,14 @@
+pub mod ban;
 pub mod delete_node;
 pub mod edit_node;
 pub mod flags;
 pub mod lifecycle;
+pub mod lock;
+pub mod migration;
+pub mod moderation;
+pub mod moderators;
+pub mod ownership;
+pub mod recycle_bin;
 pub mod reply;
 pub mod set_config;
 pub mod tip;
@@ -0,0 +1,138 @@
+use crate::{
+    error::ContractError,
+    msg::FlagResolution,
+    state::{
+        ensure_not_terminated,
+        models::{NodeMetadata, Tombstone, ROOT_ID},
+        permissions::{authorize, Action, Role},
+        storage::{IX_FLAGGED, NODE_ID_2_FLAG, NODE_ID_2_METADATA},
+    },
+    util::load_node_metadata,
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Order, Response, Storage};
+
+use super::{
+    delete_node::{process_deletions, schedule_deletion, DEFAULT_DELETION_BATCH_SIZE},
+    Context,
+};
+
+/// Restores a node that was hidden pending review, dismissing the flags that
+/// caused it to be hidden so that it isn't immediately hidden again. Hidden
+/// nodes can be removed permanently through the regular Delete action.
+///
+/// Authorization: Owner or moderators (see Action::Moderate).
+pub fn exec_unhide(
+    ctx: Context,
+    id: u32,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+
+    authorize_moderator(&deps, &info.sender)?;
+
+    let mut metadata = load_node_metadata(deps.storage, id, true)?.unwrap();
+
+    if metadata.hidden_at.is_none() {
+        return Err(ContractError::ValidationError {
+            reason: format!("Node {} is not hidden", id),
+        });
+    }
+
+    dismiss_flags(deps.storage, &mut metadata)?;
+
+    metadata.hidden_at = None;
+    NODE_ID_2_METADATA.save(deps.storage, id, &metadata)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "unhide"),
+        attr("node_id", id.to_string()),
+    ]))
+}
+
+/// Resolves the flags placed on a node, removing it from the moderation
+/// queue. Depending on the resolution, the node is either restored (Dismiss),
+/// hidden regardless of its remaining flag count (Hide), or deleted along
+/// with its replies (Delete).
+///
+/// Authorization: Same as exec_unhide.
+pub fn exec_resolve_flags(
+    ctx: Context,
+    id: u32,
+    resolution: FlagResolution,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, env } = ctx;
+
+    let role = authorize_moderator(&deps, &info.sender)?;
+
+    let mut metadata = load_node_metadata(deps.storage, id, true)?.unwrap();
+
+    if metadata.n_flags == 0 {
+        return Err(ContractError::ValidationError {
+            reason: format!("Node {} has not been flagged", id),
+        });
+    }
+
+    let resolution_str = match resolution {
+        FlagResolution::Dismiss => {
+            dismiss_flags(deps.storage, &mut metadata)?;
+            metadata.hidden_at = None;
+            NODE_ID_2_METADATA.save(deps.storage, id, &metadata)?;
+            "dismiss"
+        },
+        FlagResolution::Hide => {
+            dismiss_flags(deps.storage, &mut metadata)?;
+            metadata.hidden_at = metadata.hidden_at.or(Some(env.block.time));
+            NODE_ID_2_METADATA.save(deps.storage, id, &metadata)?;
+            "hide"
+        },
+        FlagResolution::Delete => {
+            if id == ROOT_ID {
+                return Err(ContractError::ValidationError {
+                    reason: "The root node can only be deleted through Delete".to_owned(),
+                });
+            }
+            let tombstone = Tombstone {
+                deleted_at: env.block.time,
+                deleted_by: info.sender.clone(),
+                role,
+            };
+            schedule_deletion(deps.storage, &metadata, &tombstone)?;
+            process_deletions(deps.storage, DEFAULT_DELETION_BATCH_SIZE)?;
+            "delete"
+        },
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "resolve_flags"),
+        attr("node_id", id.to_string()),
+        attr("resolution", resolution_str),
+    ]))
+}
+
+fn authorize_moderator(
+    deps: &DepsMut,
+    principal: &Addr,
+) -> Result<Role, ContractError> {
+    ensure_not_terminated(deps.storage)?;
+    authorize(deps.as_ref(), principal, Action::Moderate, None)
+}
+
+/// Removes all flags placed on a node, resets its flag count and removes it
+/// from the moderation queue. The caller is responsible for saving the
+/// updated metadata.
+pub fn dismiss_flags(
+    store: &mut dyn Storage,
+    metadata: &mut NodeMetadata,
+) -> Result<(), ContractError> {
+    let id = metadata.id;
+    let addrs: Vec<Addr> = NODE_ID_2_FLAG
+        .prefix(id)
+        .keys(store, None, None, Order::Ascending)
+        .map(|r| r.unwrap())
+        .collect();
+    for addr in addrs.iter() {
+        NODE_ID_2_FLAG.remove(store, (id, addr));
+    }
+    IX_FLAGGED.remove(store, (metadata.n_flags, id));
+    metadata.n_flags = 0;
+    Ok(())
+}
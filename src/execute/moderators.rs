@@ -0,0 +1,45 @@
+use crate::{
+    error::ContractError,
+    state::{
+        ensure_not_terminated,
+        permissions::{authorize, Action},
+        storage::MODERATORS,
+    },
+};
+use cosmwasm_std::{attr, Addr, Response};
+
+use super::Context;
+
+/// Grants and revokes the moderator role. Removals are applied after
+/// additions, so an address present in both lists ends up without the role.
+///
+/// Authorization: Owner only (see Action::ManageModerators).
+pub fn exec_update_moderators(
+    ctx: Context,
+    add: Option<Vec<Addr>>,
+    remove: Option<Vec<Addr>>,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    authorize(deps.as_ref(), &info.sender, Action::ManageModerators, None)?;
+
+    let add = add.unwrap_or_default();
+    let remove = remove.unwrap_or_default();
+
+    for addr in add.iter() {
+        let addr = deps.api.addr_validate(addr.as_str())?;
+        MODERATORS.save(deps.storage, &addr, &true)?;
+    }
+
+    for addr in remove.iter() {
+        MODERATORS.remove(deps.storage, addr);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_moderators"),
+        attr("n_added", add.len().to_string()),
+        attr("n_removed", remove.len().to_string()),
+    ]))
+}
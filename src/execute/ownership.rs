@@ -0,0 +1,131 @@
+use crate::{
+    error::ContractError,
+    state::{
+        ensure_not_terminated,
+        models::PendingOwner,
+        permissions::{authorize, is_authorized, Action},
+        storage::{DEFAULT_OWNERSHIP_TRANSFER_TTL_SECONDS, OWNER, PENDING_OWNER},
+    },
+};
+use cosmwasm_std::{attr, Response, Storage, Timestamp};
+use cw_lib::models::Owner;
+
+use super::Context;
+
+/// Proposes a new owner for the thread. Ownership only changes hands once the
+/// proposed owner accepts before the proposal expires. Proposing a new owner
+/// replaces any pending proposal.
+///
+/// Authorization: Owner only (see Action::TransferOwnership).
+pub fn exec_transfer_ownership(
+    ctx: Context,
+    new_owner: Owner,
+    expires_at: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    authorize(deps.as_ref(), &info.sender, Action::TransferOwnership, None)?;
+
+    deps.api.addr_validate(new_owner.to_addr().as_str())?;
+
+    let expires_at = expires_at.unwrap_or_else(|| {
+        env.block
+            .time
+            .plus_seconds(DEFAULT_OWNERSHIP_TRANSFER_TTL_SECONDS)
+    });
+
+    if expires_at <= env.block.time {
+        return Err(ContractError::ValidationError {
+            reason: "Ownership transfer expiry must be in the future".to_owned(),
+        });
+    }
+
+    PENDING_OWNER.save(
+        deps.storage,
+        &PendingOwner {
+            owner: new_owner.clone(),
+            proposed_by: info.sender.clone(),
+            proposed_at: env.block.time,
+            expires_at,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "transfer_ownership"),
+        attr("pending_owner", new_owner.to_addr().to_string()),
+        attr("expires_at", expires_at.seconds().to_string()),
+    ]))
+}
+
+/// Completes a pending ownership transfer.
+///
+/// Authorization: Only the proposed owner's address (the ACL contract itself
+/// in the case of an ACL owner) can accept.
+pub fn exec_accept_ownership(ctx: Context) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    let pending = load_pending_owner(deps.storage)?;
+
+    if pending.expires_at <= env.block.time {
+        return Err(ContractError::ValidationError {
+            reason: "Ownership transfer has expired".to_owned(),
+        });
+    }
+
+    if pending.owner.to_addr() != info.sender {
+        return Err(ContractError::NotAuthorized {
+            reason: "Only the pending owner can accept ownership".to_owned(),
+        });
+    }
+
+    let prev_owner = OWNER.load(deps.storage)?;
+
+    OWNER.save(deps.storage, &pending.owner)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("prev_owner", prev_owner.to_addr().to_string()),
+        attr("owner", pending.owner.to_addr().to_string()),
+    ]))
+}
+
+/// Cancels a pending ownership transfer, whether or not it has expired.
+///
+/// Authorization: The current owner, as in exec_transfer_ownership, or the
+/// proposed owner, who may decline.
+pub fn exec_cancel_ownership_transfer(ctx: Context) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    let pending = load_pending_owner(deps.storage)?;
+
+    if !(pending.owner.to_addr() == info.sender
+        || is_authorized(deps.as_ref(), &info.sender, Action::TransferOwnership, None)?)
+    {
+        return Err(ContractError::NotAuthorized {
+            reason: "Not authorized to cancel ownership transfer".to_owned(),
+        });
+    }
+
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "cancel_ownership_transfer"),
+        attr("pending_owner", pending.owner.to_addr().to_string()),
+    ]))
+}
+
+fn load_pending_owner(store: &dyn Storage) -> Result<PendingOwner, ContractError> {
+    match PENDING_OWNER.may_load(store)? {
+        Some(pending) => Ok(pending),
+        None => Err(ContractError::ValidationError {
+            reason: "No pending ownership transfer".to_owned(),
+        }),
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    error::ContractError,
+    state::{
+        ensure_not_terminated,
+        models::{NodeMetadata, RecycledNode, Tombstone},
+        permissions::{authorize, Action},
+        storage::{
+            CONFIG_RECYCLE_BIN_RETENTION, DEFAULT_RECYCLE_BIN_RETENTION_SECONDS, IX_AUTHOR_NODE,
+            IX_RECYCLE_BIN_DELETED_AT, IX_RECYCLE_BIN_EXPIRY, NODE_ID_2_BODY, NODE_ID_2_METADATA,
+            NODE_ID_2_SECTION, NODE_ID_2_TITLE, NODE_ID_2_TOMBSTONE, RECYCLE_BIN,
+        },
+        views::NodeView,
+    },
+    util::{find_pending_deletion, index_child, index_mentions, index_tags, load_node_metadata},
+};
+use cosmwasm_std::{attr, Order, Response, Storage, Timestamp};
+use cw_storage_plus::Bound;
+
+use super::Context;
+
+/// Max number of expired entries removed from the recycle bin each time a
+/// node is added to it.
+const RECYCLE_BIN_PRUNE_LIMIT: usize = 5;
+
+/// Copies a node's content into the recycle bin before it is deleted, unless
+/// the recycle bin is disabled. A node that is hard deleted after being soft
+/// deleted keeps the content recycled by the soft delete.
+pub fn recycle_node(
+    store: &mut dyn Storage,
+    node: &NodeMetadata,
+    tombstone: &Tombstone,
+) -> Result<(), ContractError> {
+    prune_recycle_bin(store, tombstone.deleted_at)?;
+
+    let retention = CONFIG_RECYCLE_BIN_RETENTION
+        .may_load(store)?
+        .unwrap_or(DEFAULT_RECYCLE_BIN_RETENTION_SECONDS);
+
+    if retention == 0 || RECYCLE_BIN.has(store, node.id) {
+        return Ok(());
+    }
+
+    let view = NodeView::load(store, node.id, &None)?;
+    let expires_at = tombstone.deleted_at.plus_seconds(retention);
+
+    RECYCLE_BIN.save(
+        store,
+        node.id,
+        &RecycledNode {
+            metadata: node.clone(),
+            title: view.title,
+            body: view.body,
+            sections: view.sections,
+            tags: view.tags,
+            mentions: view.mentions,
+            deleted: tombstone.clone(),
+            expires_at,
+        },
+    )?;
+    IX_RECYCLE_BIN_EXPIRY.save(store, (expires_at.seconds(), node.id), &true)?;
+    IX_RECYCLE_BIN_DELETED_AT.save(store, (tombstone.deleted_at.seconds(), node.id), &true)?;
+
+    Ok(())
+}
+
+/// Restores a deleted node from the recycle bin. A soft deleted node gets its
+/// content back in place. A hard deleted node is reattached to its parent,
+/// which must exist, so deleted subtrees are restored from the top down, one
+/// node at a time. Votes, flags and edit history are not restored. Tags and
+/// mentions are restored as they were, even if limits have since been lowered.
+///
+/// Authorization: Owner or moderators (see Action::Restore).
+pub fn exec_restore(
+    ctx: Context,
+    id: u32,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+
+    ensure_not_terminated(deps.storage)?;
+
+    authorize(deps.as_ref(), &info.sender, Action::Restore, None)?;
+
+    let recycled = match RECYCLE_BIN.may_load(deps.storage, id)? {
+        Some(recycled) if recycled.expires_at > env.block.time => recycled,
+        _ => {
+            return Err(ContractError::ValidationError {
+                reason: format!("Node {} is not in the recycle bin", id),
+            })
+        },
+    };
+
+    if find_pending_deletion(deps.storage, id)?.is_some() {
+        return Err(ContractError::ValidationError {
+            reason: format!("Node {} is still being deleted", id),
+        });
+    }
+
+    let RecycledNode {
+        metadata,
+        title,
+        body,
+        sections,
+        tags,
+        mentions,
+        deleted,
+        expires_at,
+    } = recycled;
+
+    let n_sections = u8::try_from(sections.len()).map_err(|_| ContractError::ValidationError {
+        reason: format!("Node {} has too many sections to restore", id),
+    })?;
+
+    // Check that a hard deleted node's parent exists before changing anything
+    let current = load_node_metadata(deps.storage, id, false)?;
+    let maybe_parent = match (&current, metadata.parent_id) {
+        (None, Some(parent_id)) => match load_node_metadata(deps.storage, parent_id, false)? {
+            Some(parent) => Some(parent),
+            None => {
+                return Err(ContractError::ValidationError {
+                    reason: format!("The parent of node {} must be restored first", id),
+                })
+            },
+        },
+        _ => None,
+    };
+
+    RECYCLE_BIN.remove(deps.storage, id);
+    IX_RECYCLE_BIN_EXPIRY.remove(deps.storage, (expires_at.seconds(), id));
+    IX_RECYCLE_BIN_DELETED_AT.remove(deps.storage, (deleted.deleted_at.seconds(), id));
+
+    let mut metadata = match (current, maybe_parent) {
+        // Soft deleted, so the node is still in place
+        (Some(current), _) => {
+            NODE_ID_2_TOMBSTONE.remove(deps.storage, id);
+            current
+        },
+        // Hard deleted, so the node must be reattached to its parent
+        (None, Some(mut parent)) => {
+            let metadata = NodeMetadata {
+                rank: 0,
+                n_upvotes: 0,
+                n_downvotes: 0,
+                n_replies: 0,
+                n_flags: 0,
+                hidden_at: None,
+                ..metadata
+            };
+            index_child(deps.storage, &metadata)?;
+            IX_AUTHOR_NODE.save(deps.storage, (&metadata.created_by, id), &true)?;
+            parent.n_replies += 1;
+            NODE_ID_2_METADATA.save(deps.storage, parent.id, &parent)?;
+            metadata
+        },
+        // The root node is never hard deleted on its own
+        (None, None) => {
+            return Err(ContractError::NodeNotFound { node_id: id });
+        },
+    };
+
+    // Restore content
+    match title {
+        Some(title) => NODE_ID_2_TITLE.save(deps.storage, id, &title)?,
+        None => NODE_ID_2_TITLE.remove(deps.storage, id),
+    }
+    NODE_ID_2_BODY.save(deps.storage, id, &body)?;
+    for (i, section) in sections.iter().enumerate() {
+        NODE_ID_2_SECTION.save(deps.storage, (id, i as u8), section)?;
+    }
+    metadata.n_sections = n_sections;
+    index_tags(deps.storage, id, Some(tags), true)?;
+    index_mentions(deps.storage, id, Some(mentions), true)?;
+
+    NODE_ID_2_METADATA.save(deps.storage, id, &metadata)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "restore"),
+        attr("node_id", id.to_string()),
+    ]))
+}
+
+/// Removes a few of the recycle bin's expired entries, oldest first.
+fn prune_recycle_bin(
+    store: &mut dyn Storage,
+    time: Timestamp,
+) -> Result<(), ContractError> {
+    let expired = IX_RECYCLE_BIN_EXPIRY
+        .keys(
+            store,
+            None,
+            Some(Bound::inclusive((time.seconds(), u32::MAX))),
+            Order::Ascending,
+        )
+        .take(RECYCLE_BIN_PRUNE_LIMIT)
+        .collect::<Result<Vec<_>, _>>()?;
+    for (expiry, id) in expired {
+        IX_RECYCLE_BIN_EXPIRY.remove(store, (expiry, id));
+        if let Some(recycled) = RECYCLE_BIN.may_load(store, id)? {
+            IX_RECYCLE_BIN_DELETED_AT.remove(store, (recycled.deleted.deleted_at.seconds(), id));
+            RECYCLE_BIN.remove(store, id);
+        }
+    }
+    Ok(())
+}
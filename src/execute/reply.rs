@@ -2,13 +2,16 @@ use crate::{
     error::ContractError,
     msg::NodeReplyMsg,
     state::{
-        models::{NodeMetadata, TableMetadata},
+        ensure_active, ensure_not_banned,
+        models::{Limits, NodeMetadata, TableMetadata},
         storage::{
-            ACTIVITY_SCORE, IX_CHILD, IX_RANKED_CHILD, NODE_ID_2_BODY, NODE_ID_2_METADATA,
-            NODE_ID_2_SECTION, N_TOTAL_REPLIES, TABLE,
+            ACTIVITY_SCORE, IX_AUTHOR_NODE, NODE_ID_2_BODY, NODE_ID_2_METADATA, NODE_ID_2_SECTION,
+            N_TOTAL_REPLIES, TABLE,
         },
     },
-    util::{next_node_id, process_tags_and_mentions},
+    util::{
+        ensure_not_deleted, ensure_unlocked, index_child, next_node_id, process_tags_and_mentions,
+    },
     validation::{validate_body, validate_mentions, validate_sections, validate_tags},
 };
 use cosmwasm_std::{attr, Response};
@@ -29,11 +32,19 @@ pub fn exec_reply(
         tags,
     } = msg;
 
+    ensure_active(deps.storage)?;
+    ensure_not_banned(deps.storage, &info.sender, env.block.time)?;
+
+    // Abort if the parent or any of its ancestors is closed to new replies
+    ensure_unlocked(deps.storage, parent_id)?;
+    ensure_not_deleted(deps.storage, parent_id)?;
+
     // Validate all input
-    validate_body(&body)?;
-    validate_tags(&tags)?;
-    validate_mentions(&mentions)?;
-    validate_sections(&sections)?;
+    let limits = Limits::load(deps.storage)?;
+    validate_body(&body, &limits)?;
+    validate_tags(&tags, &limits)?;
+    validate_mentions(&mentions, &limits)?;
+    validate_sections(&sections, &limits)?;
 
     let mut parent_depth: Option<u8> = None;
 
@@ -70,7 +81,7 @@ pub fn exec_reply(
     // Save sections
     let mut n_sections: u8 = 0;
     for (i, section) in sections.unwrap_or_default().iter().enumerate() {
-        NODE_ID_2_SECTION.save(deps.storage, (child_id, i as u8), &section)?;
+        NODE_ID_2_SECTION.save(deps.storage, (child_id, i as u8), section)?;
         n_sections += 1;
     }
 
@@ -86,15 +97,19 @@ pub fn exec_reply(
         n_replies: 0,
         rank: 0,
         n_flags: 0,
+        hidden_at: None,
+        n_revisions: 0,
+        n_upvotes: 0,
+        n_downvotes: 0,
     };
 
     NODE_ID_2_METADATA.save(deps.storage, child_id, &child_metadata)?;
 
-    // Add to parent-child relationship
-    IX_CHILD.save(deps.storage, (parent_id, child_id), &true)?;
+    // Add to parent-child relationship and reply orderings
+    index_child(deps.storage, &child_metadata)?;
 
-    // Add to ranked reply relationship
-    IX_RANKED_CHILD.save(deps.storage, (parent_id, 0, child_id), &true)?;
+    // Add to author's nodes
+    IX_AUTHOR_NODE.save(deps.storage, (&info.sender, child_id), &true)?;
 
     process_tags_and_mentions(deps.storage, child_id, tags, mentions, false)?;
 
@@ -2,12 +2,17 @@ use crate::{
     error::ContractError,
     msg::ConfigUpdateMsg,
     state::{
-        models::ROOT_ID,
-        storage::{CONFIG_TIP_TOKEN_ALLOWLIST, MAX_TIP_TOKEN_TYPES, TIP_TOKEN_LUTAB},
+        ensure_not_terminated,
+        permissions::{authorize, Action},
+        storage::{
+            CONFIG_EDIT_POLICY, CONFIG_FLAG_THRESHOLD, CONFIG_LIMITS, CONFIG_PRIVATE_VOTES,
+            CONFIG_RECYCLE_BIN_RETENTION, CONFIG_TIP_TOKEN_ALLOWLIST, MAX_TIP_TOKEN_TYPES,
+            TIP_TOKEN_LUTAB,
+        },
     },
-    util::load_node_metadata,
+    validation::{validate_edit_policy, validate_flag_threshold, validate_limit_overrides},
 };
-use cosmwasm_std::{attr, Response};
+use cosmwasm_std::{attr, Response, Storage};
 
 use super::Context;
 
@@ -16,15 +21,22 @@ pub fn exec_set_config(
     updates: ConfigUpdateMsg,
 ) -> Result<Response, ContractError> {
     let Context { deps, info, .. } = ctx;
-    let node = load_node_metadata(deps.storage, ROOT_ID, true)?.unwrap();
+
+    ensure_not_terminated(deps.storage)?;
 
     // Only thread owner can update config
-    if info.sender != node.created_by {
-        return Err(ContractError::NotAuthorized {
-            reason: "You do not own the thread".to_owned(),
-        });
-    }
+    authorize(deps.as_ref(), &info.sender, Action::SetConfig, None)?;
+
+    update_config(deps.storage, &updates)?;
 
+    Ok(Response::new().add_attributes(vec![attr("action", "set_config")]))
+}
+
+/// Validates and applies config updates. Fields left unset are unchanged.
+pub fn update_config(
+    store: &mut dyn Storage,
+    updates: &ConfigUpdateMsg,
+) -> Result<(), ContractError> {
     // Update accepted tip token types
     if let Some(tokens) = &updates.tip_tokens {
         if tokens.len() > MAX_TIP_TOKEN_TYPES {
@@ -32,12 +44,40 @@ pub fn exec_set_config(
                 reason: format!("Max number of tip token types is {}", MAX_TIP_TOKEN_TYPES),
             });
         }
-        CONFIG_TIP_TOKEN_ALLOWLIST.save(deps.storage, tokens)?;
-        TIP_TOKEN_LUTAB.clear(deps.storage);
+        CONFIG_TIP_TOKEN_ALLOWLIST.save(store, tokens)?;
+        TIP_TOKEN_LUTAB.clear(store);
         for token in tokens.iter() {
-            TIP_TOKEN_LUTAB.save(deps.storage, &token.get_key(), &true)?;
+            TIP_TOKEN_LUTAB.save(store, &token.get_key(), &true)?;
         }
     }
 
-    Ok(Response::new().add_attributes(vec![attr("action", "set_config")]))
+    // Update threshold at which flagged nodes are hidden pending review
+    if let Some(threshold) = &updates.flag_threshold {
+        validate_flag_threshold(threshold)?;
+        CONFIG_FLAG_THRESHOLD.save(store, threshold)?;
+    }
+
+    // Update content limits. Existing content isn't revalidated.
+    if let Some(overrides) = &updates.limits {
+        validate_limit_overrides(overrides)?;
+        CONFIG_LIMITS.save(store, overrides)?;
+    }
+
+    // Update restrictions on when posts may be edited
+    if let Some(policy) = &updates.edit_policy {
+        validate_edit_policy(policy)?;
+        CONFIG_EDIT_POLICY.save(store, policy)?;
+    }
+
+    // Update how long deleted nodes can be restored
+    if let Some(retention) = updates.recycle_bin_retention_seconds {
+        CONFIG_RECYCLE_BIN_RETENTION.save(store, &retention)?;
+    }
+
+    // Update whether voters are hidden
+    if let Some(private_votes) = updates.private_votes {
+        CONFIG_PRIVATE_VOTES.save(store, &private_votes)?;
+    }
+
+    Ok(())
 }
@@ -1,6 +1,7 @@
 use crate::{
     error::ContractError,
     state::{
+        ensure_active, ensure_not_banned,
         models::{TableMetadata, ROOT_ID},
         storage::{TIP_TOKEN_LUTAB, TOTAL_TIP_AMOUNTS},
     },
@@ -22,6 +23,9 @@ pub fn exec_tip(
 ) -> Result<Response, ContractError> {
     let Context { deps, info, env } = ctx;
 
+    ensure_active(deps.storage)?;
+    ensure_not_banned(deps.storage, &info.sender, env.block.time)?;
+
     // Load the thread's creator address
     let creator = load_node_metadata(deps.storage, ROOT_ID, true)?
         .unwrap()
@@ -98,15 +102,15 @@ fn tip_cw20(
     // Check if this CW20 token is in the allowlist
     let token_key = cw20_addr.to_string();
     if !TIP_TOKEN_LUTAB.has(store, &token_key) {
-        return Err(ContractError::UnauthorizedTipToken {
-            token: token_key,
-        });
+        return Err(ContractError::UnauthorizedTipToken { token: token_key });
     }
 
     // Update total tip amounts for this token
-    TOTAL_TIP_AMOUNTS.update(store, &token_key, |maybe_total| -> Result<_, ContractError> {
-        Ok(maybe_total.unwrap_or_default() + amount)
-    })?;
+    TOTAL_TIP_AMOUNTS.update(
+        store,
+        &token_key,
+        |maybe_total| -> Result<_, ContractError> { Ok(maybe_total.unwrap_or_default() + amount) },
+    )?;
 
     // Build CW20 transfer message
     let transfer_msg = Cw20ExecuteMsg::Transfer {
@@ -120,13 +124,11 @@ fn tip_cw20(
         funds: vec![],
     };
 
-    Ok(Response::new()
-        .add_message(wasm_msg)
-        .add_attributes(vec![
-            attr("action", "tip"),
-            attr("tip_amount", amount.to_string()),
-            attr("tip_token", cw20_addr.to_string()),
-        ]))
+    Ok(Response::new().add_message(wasm_msg).add_attributes(vec![
+        attr("action", "tip"),
+        attr("tip_amount", amount.to_string()),
+        attr("tip_token", cw20_addr.to_string()),
+    ]))
 }
 
 fn increment_total_tip_amount(
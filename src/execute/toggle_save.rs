@@ -1,9 +1,11 @@
 use crate::{
     error::ContractError,
     state::{
+        ensure_active,
         models::TableMetadata,
-        storage::{IX_ADDR_SAVED_ID, NODE_ID_2_METADATA, TABLE},
+        storage::{IX_ADDR_SAVED_AT, NODE_ID_2_METADATA, NODE_ID_ADDR_2_SAVED_AT, TABLE},
     },
+    util::ensure_not_deleted,
 };
 use cosmwasm_std::{attr, Response};
 use cw_table::{
@@ -19,6 +21,9 @@ pub fn exec_toggle_save(
     node_ids: Vec<u32>,
 ) -> Result<Response, ContractError> {
     let Context { deps, info, env } = ctx;
+
+    ensure_active(deps.storage)?;
+
     let maybe_table_metadata = TABLE.may_load(deps.storage)?;
     let using_table = maybe_table_metadata.is_some();
 
@@ -29,21 +34,37 @@ pub fn exec_toggle_save(
         vec![]
     };
 
-    // Add addr + each node ID to ADDR_SAVED_TO index for use in determining if
-    // a given address has saved a given node ID.
+    // Record when the sender saved each node, for use in determining if a
+    // given address has saved a given node ID, and index the node by save time
+    // for listing the address's saved nodes. Saving a node again keeps the
+    // time it was first saved.
     for node_id in node_ids.iter() {
-        if NODE_ID_2_METADATA.has(deps.storage, *node_id) {
-            let ix_key = (&info.sender, *node_id);
+        // Deleted nodes can't be newly saved, though they can still be unsaved
+        let is_deleted = is_saving && ensure_not_deleted(deps.storage, *node_id).is_err();
+        if NODE_ID_2_METADATA.has(deps.storage, *node_id) && !is_deleted {
+            let maybe_saved_at =
+                NODE_ID_ADDR_2_SAVED_AT.may_load(deps.storage, (*node_id, &info.sender))?;
             if is_saving {
-                IX_ADDR_SAVED_ID.update(deps.storage, ix_key, |x| -> Result<_, ContractError> {
-                    if x.is_none() && using_table {
+                if maybe_saved_at.is_none() {
+                    let saved_at = env.block.time;
+                    NODE_ID_ADDR_2_SAVED_AT.save(
+                        deps.storage,
+                        (*node_id, &info.sender),
+                        &saved_at,
+                    )?;
+                    IX_ADDR_SAVED_AT.save(
+                        deps.storage,
+                        (&info.sender, saved_at.seconds(), *node_id),
+                        &true,
+                    )?;
+                    if using_table {
                         newly_saved_ids.push(*node_id);
                     }
-                    Ok(true)
-                })?;
-            } else {
+                }
+            } else if let Some(saved_at) = maybe_saved_at {
                 // => is unsaving
-                IX_ADDR_SAVED_ID.remove(deps.storage, ix_key);
+                NODE_ID_ADDR_2_SAVED_AT.remove(deps.storage, (*node_id, &info.sender));
+                IX_ADDR_SAVED_AT.remove(deps.storage, (&info.sender, saved_at.seconds(), *node_id));
             }
         }
     }
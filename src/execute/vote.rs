@@ -2,9 +2,11 @@ use crate::{
     error::ContractError,
     msg::NodeVoteMsg,
     state::{
-        models::{TableMetadata, NIL, ROOT_ID, UP},
-        storage::{IX_RANKED_CHILD, NODE_ID_2_METADATA, NODE_ID_ADDR_2_SENTIMENT, TABLE},
+        ensure_active, ensure_not_banned,
+        models::{NodeMetadata, TableMetadata, NIL, ROOT_ID, UP},
+        storage::{ADDR_NODE_ID_2_SENTIMENT, NODE_ID_2_METADATA, NODE_ID_ADDR_2_SENTIMENT, TABLE},
     },
+    util::{ensure_not_deleted, ensure_unlocked, index_child, unindex_child},
 };
 use cosmwasm_std::{attr, Response, Storage};
 use cw_table::{client::Table, msg::KeyValue};
@@ -25,42 +27,54 @@ pub fn exec_votes(
     let Context { deps, info, env } = ctx;
     let mut resp = Response::new().add_attributes(vec![attr("action", "vote")]);
 
+    ensure_active(deps.storage)?;
+    ensure_not_banned(deps.storage, &info.sender, env.block.time)?;
+
     for msg in msgs.iter() {
         let child_id = msg.id;
 
+        ensure_unlocked(deps.storage, child_id)?;
+        ensure_not_deleted(deps.storage, child_id)?;
+
         let new_user_sentiment_u8 = msg.sentiment.to_u8();
         let old_user_sentiment_u8 = NODE_ID_ADDR_2_SENTIMENT
             .may_load(deps.storage, (child_id, &info.sender))?
             .unwrap_or(NIL);
 
         // Get the sender's previous vote sentiment WRT to the voted node and
-        // update it according to the new vote.
+        // update it according to the new vote, along with the reverse entry
+        // used to list the sender's votes.
         if new_user_sentiment_u8 != old_user_sentiment_u8 {
             NODE_ID_ADDR_2_SENTIMENT.save(
                 deps.storage,
                 (child_id, &info.sender),
                 &new_user_sentiment_u8,
             )?;
+            ADDR_NODE_ID_2_SENTIMENT.save(
+                deps.storage,
+                (&info.sender, child_id),
+                &new_user_sentiment_u8,
+            )?;
         } else {
-            // Remove the entry if the existing up or down vote is being unset.
+            // Remove the entries if the existing up or down vote is being unset.
             NODE_ID_ADDR_2_SENTIMENT.remove(deps.storage, (child_id, &info.sender));
+            ADDR_NODE_ID_2_SENTIMENT.remove(deps.storage, (&info.sender, child_id));
         }
 
-        // Update the metadata of the node voted on and return the updated metadata
-        // as well as the node's "previous" sentiment and rank prior to the update.
-        // We use this below to update the node's relationships.
-        let (maybe_parent_id, prev_rank, curr_rank) = update_node_rank(
+        // Update the metadata of the node voted on and return its metadata
+        // both prior to and after the update. We use this below to update the
+        // node's relationships.
+        let (prev_metadata, curr_metadata) = update_node_rank(
             deps.storage,
             child_id,
             old_user_sentiment_u8,
             new_user_sentiment_u8,
         )?;
+        let curr_rank = curr_metadata.rank;
 
-        // Update ranking of voted node WRT its parent node
-        if let Some(parent_id) = maybe_parent_id {
-            IX_RANKED_CHILD.remove(deps.storage, (parent_id, prev_rank, child_id));
-            IX_RANKED_CHILD.save(deps.storage, (parent_id, curr_rank, child_id), &true)?;
-        }
+        // Update orderings of voted node WRT its parent node
+        unindex_child(deps.storage, &prev_metadata);
+        index_child(deps.storage, &curr_metadata)?;
 
         // Prepare data for updating the thread's table if applicable
         if child_id == ROOT_ID {
@@ -84,35 +98,41 @@ pub fn update_node_rank(
     node_id: u32,
     old_user_sentiment: u8,
     new_user_sentiment: u8,
-) -> Result<(Option<u32>, i32, i32), ContractError> {
-    let mut parent_id: Option<u32> = None;
-    let mut prev_rank = 0;
-    let mut curr_rank = 0;
+) -> Result<(NodeMetadata, NodeMetadata), ContractError> {
+    let mut prev_metadata: Option<NodeMetadata> = None;
 
-    NODE_ID_2_METADATA.update(
+    let curr_metadata = NODE_ID_2_METADATA.update(
         store,
         node_id,
         |maybe_metadata| -> Result<_, ContractError> {
             if let Some(mut meta) = maybe_metadata {
-                parent_id = meta.parent_id;
-                prev_rank = meta.rank;
+                prev_metadata = Some(meta.clone());
                 if old_user_sentiment == new_user_sentiment {
                     // Undo/untoggle existing vote
                     if new_user_sentiment == UP {
                         meta.rank -= 1;
+                        meta.n_upvotes = meta.n_upvotes.saturating_sub(1);
                     } else {
                         meta.rank += 1;
+                        meta.n_downvotes = meta.n_downvotes.saturating_sub(1);
                     }
                 } else {
                     // Set or update vote
                     let delta = if old_user_sentiment == NIL { 1 } else { 2 };
                     if new_user_sentiment == UP {
                         meta.rank += delta;
+                        meta.n_upvotes += 1;
+                        if old_user_sentiment != NIL {
+                            meta.n_downvotes = meta.n_downvotes.saturating_sub(1);
+                        }
                     } else {
                         meta.rank -= delta;
+                        meta.n_downvotes += 1;
+                        if old_user_sentiment != NIL {
+                            meta.n_upvotes = meta.n_upvotes.saturating_sub(1);
+                        }
                     }
                 }
-                curr_rank = meta.rank;
                 Ok(meta)
             } else {
                 Err(ContractError::NodeNotFound { node_id })
@@ -120,5 +140,5 @@ pub fn update_node_rank(
         },
     )?;
 
-    Ok((parent_id, prev_rank, curr_rank))
+    Ok((prev_metadata.unwrap(), curr_metadata))
 }
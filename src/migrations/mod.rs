@@ -0,0 +1,243 @@
+//! Versioned state migrations.
+//!
+//! Each release that changes the storage layout registers a Migration with the
+//! version it upgrades state to and an ordered list of steps. Migrating from
+//! version A to B runs every registered migration in (A, B], in order.
+//!
+//! Steps are batched so that large threads can be migrated across several
+//! transactions. Each call processes at most `limit` records and returns a
+//! cursor to resume from. Progress is persisted in MIGRATION, and the
+//! remaining work is picked up by ContinueMigration (or another migrate). All
+//! mutating messages are rejected until every step has completed.
+
+mod v0_0_2;
+
+use cosmwasm_std::{attr, from_json, to_json_binary, Binary, DepsMut, Env, Response, Storage};
+use cw2::{get_contract_version, set_contract_version};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    error::ContractError,
+    execute::set_config::update_config,
+    msg::MigrateMsg,
+    state::{models::MigrationState, storage::MIGRATION},
+};
+
+pub const DEFAULT_MIGRATION_BATCH_SIZE: u32 = 500;
+
+/// A resumable unit of migration work. Receives the cursor returned by its
+/// previous invocation, if any, and processes at most `limit` records.
+pub type Step = fn(
+    store: &mut dyn Storage,
+    env: &Env,
+    cursor: Option<Binary>,
+    limit: u32,
+) -> Result<StepOutcome, ContractError>;
+
+pub struct StepOutcome {
+    pub n_processed: u32,
+    /// Where to resume from, or None if the step is complete.
+    pub cursor: Option<Binary>,
+}
+
+pub struct Migration {
+    pub version: &'static str,
+    pub steps: &'static [Step],
+}
+
+/// Registered migrations in ascending version order.
+const MIGRATIONS: &[Migration] = &[v0_0_2::MIGRATION];
+
+/// Upgrades contract state to CONTRACT_VERSION. Refuses to migrate state
+/// written by a different contract or by a newer version of this one.
+pub fn migrate(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidMigration {
+            reason: format!("Cannot migrate from {}", stored.contract),
+        });
+    }
+
+    let from = parse_version(&stored.version)?;
+    let to = parse_version(CONTRACT_VERSION)?;
+
+    if from > to {
+        return Err(ContractError::InvalidMigration {
+            reason: format!(
+                "Cannot downgrade from {} to {}",
+                stored.version, CONTRACT_VERSION
+            ),
+        });
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if let Some(updates) = &msg.config {
+        update_config(deps.storage, updates)?;
+    }
+
+    // Resume an unfinished migration, if any, now targeting the new version
+    let state = match MIGRATION.may_load(deps.storage)? {
+        Some(state) => Some(MigrationState {
+            to_version: CONTRACT_VERSION.to_owned(),
+            ..state
+        }),
+        None => next_migration(from, to)?.map(|migration| MigrationState {
+            from_version: stored.version.clone(),
+            to_version: CONTRACT_VERSION.to_owned(),
+            version: migration.version.to_owned(),
+            step: 0,
+            cursor: None,
+        }),
+    };
+
+    let resp = Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("from_version", stored.version),
+        attr("to_version", CONTRACT_VERSION),
+    ]);
+
+    match state {
+        Some(state) => {
+            let limit = msg.batch_size.unwrap_or(DEFAULT_MIGRATION_BATCH_SIZE);
+            let pending = run(deps.storage, &env, state, limit)?;
+            Ok(resp.add_attribute("pending", pending.to_string()))
+        },
+        None => Ok(resp.add_attribute("pending", "false")),
+    }
+}
+
+/// Continues an unfinished migration, processing at most `limit` records.
+pub fn resume(
+    store: &mut dyn Storage,
+    env: &Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let state = match MIGRATION.may_load(store)? {
+        Some(state) => state,
+        None => {
+            return Err(ContractError::InvalidMigration {
+                reason: "No migration in progress".to_owned(),
+            })
+        },
+    };
+    let pending = run(
+        store,
+        env,
+        state,
+        limit.unwrap_or(DEFAULT_MIGRATION_BATCH_SIZE),
+    )?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "continue_migration"),
+        attr("pending", pending.to_string()),
+    ]))
+}
+
+/// Runs migration steps until either all are complete or `limit` records
+/// have been processed, persisting progress. Returns true if work remains.
+fn run(
+    store: &mut dyn Storage,
+    env: &Env,
+    mut state: MigrationState,
+    limit: u32,
+) -> Result<bool, ContractError> {
+    let to = parse_version(&state.to_version)?;
+    let mut budget = limit.max(1);
+
+    loop {
+        let migration = find_migration(&state.version)?;
+
+        if state.step as usize >= migration.steps.len() {
+            match next_migration(parse_version(&state.version)?, to)? {
+                Some(next) => {
+                    state.version = next.version.to_owned();
+                    state.step = 0;
+                    state.cursor = None;
+                    continue;
+                },
+                None => {
+                    MIGRATION.remove(store);
+                    return Ok(false);
+                },
+            }
+        }
+
+        if budget == 0 {
+            MIGRATION.save(store, &state)?;
+            return Ok(true);
+        }
+
+        let step = migration.steps[state.step as usize];
+        let outcome = step(store, env, state.cursor.take(), budget)?;
+
+        // Always count at least one record so that the loop terminates
+        budget = budget.saturating_sub(outcome.n_processed.max(1));
+
+        match outcome.cursor {
+            Some(cursor) => state.cursor = Some(cursor),
+            None => state.step += 1,
+        }
+    }
+}
+
+fn find_migration(version: &str) -> Result<&'static Migration, ContractError> {
+    match MIGRATIONS.iter().find(|m| m.version == version) {
+        Some(migration) => Ok(migration),
+        None => Err(ContractError::InvalidMigration {
+            reason: format!("Unknown migration {}", version),
+        }),
+    }
+}
+
+/// Returns the first registered migration after `from`, up to and including
+/// `to`.
+fn next_migration(
+    from: (u64, u64, u64),
+    to: (u64, u64, u64),
+) -> Result<Option<&'static Migration>, ContractError> {
+    for migration in MIGRATIONS.iter() {
+        let version = parse_version(migration.version)?;
+        if version > from && version <= to {
+            return Ok(Some(migration));
+        }
+    }
+    Ok(None)
+}
+
+/// Parses the major, minor and patch numbers of a semver version, ignoring
+/// any pre-release or build suffix.
+fn parse_version(version: &str) -> Result<(u64, u64, u64), ContractError> {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<u64> = core
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| ContractError::InvalidMigration {
+            reason: format!("Invalid version {}", version),
+        })?;
+    match parts[..] {
+        [major, minor, patch] => Ok((major, minor, patch)),
+        _ => Err(ContractError::InvalidMigration {
+            reason: format!("Invalid version {}", version),
+        }),
+    }
+}
+
+pub fn encode_cursor<T: Serialize>(cursor: &T) -> Result<Binary, ContractError> {
+    Ok(to_json_binary(cursor)?)
+}
+
+pub fn decode_cursor<T: DeserializeOwned>(
+    cursor: Option<Binary>
+) -> Result<Option<T>, ContractError> {
+    Ok(match cursor {
+        Some(cursor) => Some(from_json(&cursor)?),
+        None => None,
+    })
+}
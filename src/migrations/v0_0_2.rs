@@ -0,0 +1,157 @@
+//! Backfills state introduced after 0.0.1: the thread status, the author and
+//! moderation queue indexes, vote counts and votes by voter, and the times
+//! nodes were saved.
+
+use cosmwasm_std::{Addr, Binary, Env, Order, Storage};
+use cw_storage_plus::{Bound, Map};
+
+use crate::{
+    error::ContractError,
+    state::{
+        models::{NodeMetadata, ThreadStatus, DOWN, UP},
+        storage::{
+            ADDR_NODE_ID_2_SENTIMENT, IX_ADDR_SAVED_AT, IX_AUTHOR_NODE, IX_FLAGGED,
+            NODE_ID_2_METADATA, NODE_ID_ADDR_2_SAVED_AT, NODE_ID_ADDR_2_SENTIMENT, STATUS,
+        },
+    },
+    util::index_child,
+};
+
+use super::{decode_cursor, encode_cursor, Migration, StepOutcome};
+
+/// Saved nodes by address, without the time they were saved.
+const LEGACY_IX_ADDR_SAVED_ID: Map<(&Addr, u32), bool> = Map::new("ix_addr_saved_id");
+
+pub const MIGRATION: Migration = Migration {
+    version: "0.0.2",
+    steps: &[
+        init_status,
+        backfill_node_indexes,
+        backfill_vote_counts,
+        migrate_saved_nodes,
+    ],
+};
+
+fn init_status(
+    store: &mut dyn Storage,
+    _env: &Env,
+    _cursor: Option<Binary>,
+    _limit: u32,
+) -> Result<StepOutcome, ContractError> {
+    if STATUS.may_load(store)?.is_none() {
+        STATUS.save(store, &ThreadStatus::Active)?;
+    }
+    Ok(StepOutcome {
+        n_processed: 1,
+        cursor: None,
+    })
+}
+
+/// Indexes every node by author and, if flagged, in the moderation queue.
+fn backfill_node_indexes(
+    store: &mut dyn Storage,
+    _env: &Env,
+    cursor: Option<Binary>,
+    limit: u32,
+) -> Result<StepOutcome, ContractError> {
+    let start = decode_cursor::<u32>(cursor)?.map(Bound::exclusive);
+    let nodes = NODE_ID_2_METADATA
+        .range(store, start, None, Order::Ascending)
+        .take(limit as usize)
+        .map(|r| r.map(|(_, node)| node))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for node in nodes.iter() {
+        IX_AUTHOR_NODE.save(store, (&node.created_by, node.id), &true)?;
+        if node.n_flags > 0 {
+            IX_FLAGGED.save(store, (node.n_flags, node.id), &true)?;
+        }
+    }
+
+    let cursor = match nodes.last() {
+        Some(node) if nodes.len() == limit as usize => Some(encode_cursor(&node.id)?),
+        _ => None,
+    };
+
+    Ok(StepOutcome {
+        n_processed: nodes.len() as u32,
+        cursor,
+    })
+}
+
+/// Tallies every node's up and downvotes, indexes replies in each order that
+/// depends on them, and indexes each vote by voter.
+fn backfill_vote_counts(
+    store: &mut dyn Storage,
+    _env: &Env,
+    cursor: Option<Binary>,
+    limit: u32,
+) -> Result<StepOutcome, ContractError> {
+    let start = decode_cursor::<u32>(cursor)?.map(Bound::exclusive);
+    let nodes = NODE_ID_2_METADATA
+        .range(store, start, None, Order::Ascending)
+        .take(limit as usize)
+        .map(|r| r.map(|(_, node)| node))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for node in nodes.iter() {
+        let votes = NODE_ID_ADDR_2_SENTIMENT
+            .prefix(node.id)
+            .range(store, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()?;
+        for (addr, sentiment) in votes.iter() {
+            ADDR_NODE_ID_2_SENTIMENT.save(store, (addr, node.id), sentiment)?;
+        }
+        let node = NodeMetadata {
+            n_upvotes: votes.iter().filter(|(_, s)| *s == UP).count() as u32,
+            n_downvotes: votes.iter().filter(|(_, s)| *s == DOWN).count() as u32,
+            ..node.clone()
+        };
+        NODE_ID_2_METADATA.save(store, node.id, &node)?;
+        index_child(store, &node)?;
+    }
+
+    let cursor = match nodes.last() {
+        Some(node) if nodes.len() == limit as usize => Some(encode_cursor(&node.id)?),
+        _ => None,
+    };
+
+    Ok(StepOutcome {
+        n_processed: nodes.len() as u32,
+        cursor,
+    })
+}
+
+/// Moves saved nodes to the timestamped indexes. The time of the migration is
+/// recorded as the time they were saved.
+fn migrate_saved_nodes(
+    store: &mut dyn Storage,
+    env: &Env,
+    cursor: Option<Binary>,
+    limit: u32,
+) -> Result<StepOutcome, ContractError> {
+    let cursor = decode_cursor::<(Addr, u32)>(cursor)?;
+    let start = cursor
+        .as_ref()
+        .map(|(addr, id)| Bound::exclusive((addr, *id)));
+    let saves = LEGACY_IX_ADDR_SAVED_ID
+        .keys(store, start, None, Order::Ascending)
+        .take(limit as usize)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (addr, id) in saves.iter() {
+        LEGACY_IX_ADDR_SAVED_ID.remove(store, (addr, *id));
+        NODE_ID_ADDR_2_SAVED_AT.save(store, (*id, addr), &env.block.time)?;
+        IX_ADDR_SAVED_AT.save(store, (addr, env.block.time.seconds(), *id), &true)?;
+    }
+
+    let cursor = match saves.last() {
+        Some(save) if saves.len() == limit as usize => Some(encode_cursor(save)?),
+        _ => None,
+    };
+
+    Ok(StepOutcome {
+        n_processed: saves.len() as u32,
+        cursor,
+    })
+}
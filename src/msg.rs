@@ -1,11 +1,15 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Order, Timestamp};
 use cw_lib::models::{Owner, TokenAmountV2, TokenV2};
 use cw_table::lifecycle::LifecycleExecuteMsg;
 
 use crate::state::{
-    models::{Section, TableMetadata, DOWN, UP},
-    views::{ConfigView, NodeView},
+    models::{
+        BanMetadata, EditPolicy, FlagThreshold, LimitOverrides, Limits, PendingOwner, RecycledNode,
+        Revision, Section, TableMetadata, ThreadStatus, DOWN, UP,
+    },
+    permissions::{Action, Role},
+    views::{ConfigView, FlaggedNodeView, NodeView, SavedNodeView},
 };
 
 #[cw_serde]
@@ -34,6 +38,16 @@ pub struct NodeReplyMsg {
     pub sections: Option<Vec<Section>>,
 }
 
+#[cw_serde]
+pub enum NodeEditField {
+    Title,
+    Sections,
+    Tags,
+    Mentions,
+}
+
+/// Partially updates a node. Fields left unset are unchanged, while fields
+/// listed in `clear` are removed. A field cannot be both set and cleared.
 #[cw_serde]
 pub struct NodeEditMsg {
     pub id: u32,
@@ -42,6 +56,7 @@ pub struct NodeEditMsg {
     pub sections: Option<Vec<Section>>,
     pub tags: Option<Vec<String>>,
     pub mentions: Option<Vec<String>>,
+    pub clear: Option<Vec<NodeEditField>>,
 }
 
 #[cw_serde]
@@ -69,9 +84,25 @@ impl Sentiment {
     }
 }
 
+#[cw_serde]
+pub enum FlagResolution {
+    Dismiss,
+    Hide,
+    Delete,
+}
+
 #[cw_serde]
 pub struct ConfigUpdateMsg {
     pub tip_tokens: Option<Vec<TokenV2>>,
+    pub flag_threshold: Option<FlagThreshold>,
+    /// Replaces the thread's limit overrides as a whole.
+    pub limits: Option<LimitOverrides>,
+    pub edit_policy: Option<EditPolicy>,
+    /// Seconds deleted nodes are kept in the recycle bin. Zero disables it.
+    pub recycle_bin_retention_seconds: Option<u64>,
+    /// Stops the Voters query from listing who voted on a node. Votes remain
+    /// readable from public contract storage, so this isn't anonymity.
+    pub private_votes: Option<bool>,
 }
 
 #[cw_serde]
@@ -84,12 +115,140 @@ pub enum ExecuteMsg {
     Vote(NodeVoteMsg),
     VoteMany(Vec<NodeVoteMsg>),
     Edit(NodeEditMsg),
-    Delete { id: u32 },
-    Flag { id: u32, reason: Option<String> },
-    Unflag { id: u32 },
+    Delete {
+        id: u32,
+        hard: Option<bool>,
+    },
+    Restore {
+        id: u32,
+    },
+    Lock {
+        id: u32,
+    },
+    Unlock {
+        id: u32,
+    },
+    Flag {
+        id: u32,
+        reason: Option<String>,
+    },
+    Unflag {
+        id: u32,
+    },
+    Unhide {
+        id: u32,
+    },
+    ResolveFlags {
+        id: u32,
+        action: FlagResolution,
+    },
+    Ban {
+        address: Addr,
+        expires_at: Option<Timestamp>,
+        reason: Option<String>,
+        purge: Option<bool>,
+    },
+    Unban {
+        address: Addr,
+    },
+    UpdateModerators {
+        add: Option<Vec<Addr>>,
+        remove: Option<Vec<Addr>>,
+    },
+    TransferOwnership {
+        new_owner: Owner,
+        expires_at: Option<Timestamp>,
+    },
+    AcceptOwnership {},
+    CancelOwnershipTransfer {},
+    ContinueMigration {
+        limit: Option<u32>,
+    },
+    ContinueDeletion {
+        limit: Option<u32>,
+    },
     Tip(TokenAmountV2),
 }
 
+/// Order in which a node's replies are listed.
+#[cw_serde]
+#[derive(Copy)]
+pub enum ChildOrder {
+    /// Highest net votes first.
+    Top,
+    /// Highest net votes first, decayed over time (see NodeMetadata::hot).
+    Hot,
+    /// Highest confidence in the share of upvotes first (see
+    /// NodeMetadata::wilson_score).
+    Best,
+    Newest,
+    Oldest,
+    /// Most heavily and evenly voted first (see NodeMetadata::controversy).
+    Controversial,
+}
+
+/// Order in which nodes are listed by id, i.e. by when they were created.
+#[cw_serde]
+#[derive(Copy)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl From<SortOrder> for Order {
+    fn from(order: SortOrder) -> Self {
+        match order {
+            SortOrder::Ascending => Order::Ascending,
+            SortOrder::Descending => Order::Descending,
+        }
+    }
+}
+
+/// Matches nodes by their tags.
+#[cw_serde]
+pub enum TagFilter {
+    /// Nodes with every one of the tags.
+    AllOf(Vec<String>),
+    /// Nodes with at least one of the tags.
+    AnyOf(Vec<String>),
+}
+
+/// Position in a Children listing. Each order has its own type of cursor,
+/// which is only valid for listings in that order.
+#[cw_serde]
+pub enum ChildCursor {
+    Top { rank: i32, id: u32 },
+    Hot { hot: i64, id: u32 },
+    Best { score: u32, id: u32 },
+    Newest { id: u32 },
+    Oldest { id: u32 },
+    Controversial { controversy: u32, id: u32 },
+}
+
+impl ChildCursor {
+    pub fn id(&self) -> u32 {
+        match self {
+            ChildCursor::Top { id, .. }
+            | ChildCursor::Hot { id, .. }
+            | ChildCursor::Best { id, .. }
+            | ChildCursor::Newest { id }
+            | ChildCursor::Oldest { id }
+            | ChildCursor::Controversial { id, .. } => *id,
+        }
+    }
+
+    pub fn order(&self) -> ChildOrder {
+        match self {
+            ChildCursor::Top { .. } => ChildOrder::Top,
+            ChildCursor::Hot { .. } => ChildOrder::Hot,
+            ChildCursor::Best { .. } => ChildOrder::Best,
+            ChildCursor::Newest { .. } => ChildOrder::Newest,
+            ChildCursor::Oldest { .. } => ChildOrder::Oldest,
+            ChildCursor::Controversial { .. } => ChildOrder::Controversial,
+        }
+    }
+}
+
 #[cw_serde]
 pub enum NodesQueryMsg {
     ByIds {
@@ -98,7 +257,9 @@ pub enum NodesQueryMsg {
     },
     Children {
         id: u32,
-        cursor: Option<(u32, i32, u32)>,
+        /// Defaults to Top.
+        order: Option<ChildOrder>,
+        cursor: Option<ChildCursor>,
         sender: Option<Addr>,
         limit: Option<u8>,
     },
@@ -107,35 +268,132 @@ pub enum NodesQueryMsg {
         levels: Option<u8>,
         sender: Option<Addr>,
     },
+    /// Lists a node followed by its replies, depth first, for rendering a
+    /// whole conversation at once.
+    Subtree {
+        id: u32,
+        /// Levels of replies to include below the node. Unlimited by default.
+        max_depth: Option<u8>,
+        /// Defaults to 25, and is at most 100.
+        max_nodes: Option<u8>,
+        /// Order of each node's replies. Defaults to Top.
+        order: Option<ChildOrder>,
+        cursor: Option<Vec<ChildCursor>>,
+        sender: Option<Addr>,
+    },
     WithTag {
         tag: String,
+        /// Defaults to Descending, i.e. newest first.
+        order: Option<SortOrder>,
         cursor: Option<u32>,
+        limit: Option<u8>,
         sender: Option<Addr>,
     },
     WithMention {
         mention: String,
+        /// Defaults to Descending, i.e. newest first.
+        order: Option<SortOrder>,
         cursor: Option<u32>,
+        limit: Option<u8>,
+        sender: Option<Addr>,
+    },
+    /// Lists the nodes matching a combination of tags, optionally only those
+    /// that also have the given mention.
+    Tagged {
+        tags: TagFilter,
+        mention: Option<String>,
+        /// Defaults to Descending, i.e. newest first.
+        order: Option<SortOrder>,
+        cursor: Option<u32>,
+        limit: Option<u8>,
+        sender: Option<Addr>,
+    },
+    /// Lists the nodes created by an address, newest first.
+    ByAuthor {
+        address: Addr,
+        cursor: Option<u32>,
+        limit: Option<u8>,
+        sender: Option<Addr>,
+    },
+    /// Lists the nodes saved by an address, most recently saved first.
+    Saved {
+        address: Addr,
+        cursor: Option<(u64, u32)>,
+        limit: Option<u8>,
+    },
+    /// Lists the addresses that voted on a node, unless votes are private.
+    Voters {
+        id: u32,
+        sentiment: Option<Sentiment>,
+        cursor: Option<Addr>,
+        limit: Option<u8>,
+    },
+    Flagged {
+        cursor: Option<(u8, u32)>,
+        limit: Option<u8>,
+        sender: Option<Addr>,
+    },
+    Revisions {
+        id: u32,
+        cursor: Option<u16>,
+        limit: Option<u8>,
+        sender: Option<Addr>,
+    },
+    /// Lists the nodes in the recycle bin, most recently deleted first.
+    Deleted {
+        cursor: Option<(u64, u32)>,
+        limit: Option<u8>,
         sender: Option<Addr>,
     },
 }
 
 #[cw_serde]
 pub enum QueryMsg {
-    Thread { sender: Option<Addr> },
+    Thread {
+        sender: Option<Addr>,
+    },
     Nodes(NodesQueryMsg),
+    Bans {
+        cursor: Option<Addr>,
+        limit: Option<u8>,
+    },
+    Permissions {
+        address: Addr,
+        id: Option<u32>,
+    },
+    /// Lists the votes cast by an address in the thread. Stays available when
+    /// votes are private, which only blocks the Voters listing.
+    Votes {
+        address: Addr,
+        sentiment_filter: Option<Sentiment>,
+        cursor: Option<u32>,
+        limit: Option<u8>,
+    },
+    Config {},
+    Version {},
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    pub config: Option<ConfigUpdateMsg>,
+    /// Max number of records to migrate before deferring the rest to
+    /// ContinueMigration.
+    pub batch_size: Option<u32>,
+}
 
 #[cw_serde]
-pub struct ConfigResponse(pub ConfigView);
+pub struct ConfigResponse {
+    pub config: ConfigView,
+    pub limits: Limits,
+}
 
 #[cw_serde]
 pub struct ThreadInfoResponse {
     pub table: Option<TableMetadata>,
+    pub status: ThreadStatus,
     pub config: ConfigView,
     pub owner: Owner,
+    pub pending_owner: Option<PendingOwner>,
     pub root: NodeView,
     pub n_total_replies: u32,
     pub tips: Vec<TokenAmountV2>,
@@ -144,7 +402,13 @@ pub struct ThreadInfoResponse {
 #[cw_serde]
 pub struct NodeViewRepliesPaginationResponse {
     pub nodes: Vec<NodeView>,
-    pub cursor: Option<(u32, i32, u32)>,
+    pub cursor: Option<ChildCursor>,
+}
+
+#[cw_serde]
+pub struct NodeViewSubtreePaginationResponse {
+    pub nodes: Vec<NodeView>,
+    pub cursor: Option<Vec<ChildCursor>>,
 }
 
 #[cw_serde]
@@ -152,3 +416,70 @@ pub struct NodeViewByTagPaginationResponse {
     pub nodes: Vec<NodeView>,
     pub cursor: Option<u32>,
 }
+
+#[cw_serde]
+pub struct NodeViewByAuthorPaginationResponse {
+    pub nodes: Vec<NodeView>,
+    pub cursor: Option<u32>,
+}
+
+#[cw_serde]
+pub struct SavedNodesPaginationResponse {
+    pub nodes: Vec<SavedNodeView>,
+    pub cursor: Option<(u64, u32)>,
+}
+
+#[cw_serde]
+pub struct FlaggedNodesPaginationResponse {
+    pub nodes: Vec<FlaggedNodeView>,
+    pub cursor: Option<(u8, u32)>,
+}
+
+#[cw_serde]
+pub struct RevisionsPaginationResponse {
+    pub revisions: Vec<Revision>,
+    pub cursor: Option<u16>,
+}
+
+#[cw_serde]
+pub struct RecycledNodesPaginationResponse {
+    pub nodes: Vec<RecycledNode>,
+    pub cursor: Option<(u64, u32)>,
+}
+
+#[cw_serde]
+pub struct BansPaginationResponse {
+    pub bans: Vec<BanMetadata>,
+    pub cursor: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct VoteView {
+    pub node_id: u32,
+    pub sentiment: Sentiment,
+}
+
+#[cw_serde]
+pub struct VoterView {
+    pub address: Addr,
+    pub sentiment: Sentiment,
+}
+
+#[cw_serde]
+pub struct VotersPaginationResponse {
+    pub voters: Vec<VoterView>,
+    pub cursor: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct VotesPaginationResponse {
+    pub votes: Vec<VoteView>,
+    pub cursor: Option<u32>,
+}
+
+#[cw_serde]
+pub struct PermissionsResponse {
+    pub address: Addr,
+    pub roles: Vec<Role>,
+    pub actions: Vec<Action>,
+}
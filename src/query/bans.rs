@@ -0,0 +1,35 @@
+use cosmwasm_std::{Addr, Order};
+use cw_storage_plus::Bound;
+
+use crate::{error::ContractError, msg::BansPaginationResponse, state::storage::BANS};
+
+use super::{nodes::DEFAULT_PAGINATION_LIMIT, ReadonlyContext};
+
+/// Pages through banned addresses in ascending order, including bans that
+/// have expired but haven't been lifted.
+pub fn query_bans(
+    ctx: ReadonlyContext,
+    cursor: Option<Addr>,
+    limit: Option<u8>,
+) -> Result<BansPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let start = cursor.as_ref().map(Bound::exclusive);
+
+    let bans = BANS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(page_size)
+        .map(|r| r.map(|(_, ban)| ban))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(BansPaginationResponse {
+        cursor: if bans.len() == page_size {
+            bans.last().map(|ban| ban.address.clone())
+        } else {
+            None
+        },
+        bans,
+    })
+}
@@ -1,9 +1,26 @@
-use crate::{error::ContractError, msg::ConfigResponse, state::views::ConfigView};
+use cw2::{get_contract_version, ContractVersion};
+
+use crate::{
+    error::ContractError,
+    msg::ConfigResponse,
+    state::{models::Limits, views::ConfigView},
+};
 
 use super::ReadonlyContext;
 
+/// Returns the thread's configuration along with the content limits that
+/// replies and edits are validated against.
 pub fn query_config(ctx: ReadonlyContext) -> Result<ConfigResponse, ContractError> {
     let ReadonlyContext { deps, .. } = ctx;
     let config = ConfigView::load(deps.storage)?;
-    Ok(ConfigResponse(config))
+    Ok(ConfigResponse {
+        config,
+        limits: Limits::load(deps.storage)?,
+    })
+}
+
+/// Returns the cw2 contract name and version.
+pub fn query_version(ctx: ReadonlyContext) -> Result<ContractVersion, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    Ok(get_contract_version(deps.storage)?)
 }
@@ -5,12 +5,16 @@ use crate::{
     error::ContractError,
     msg::ThreadInfoResponse,
     state::{
-        storage::{N_TOTAL_REPLIES, OWNER, TABLE, TOTAL_TIP_AMOUNTS},
-        views::{ConfigView, NodeView},
+        models::ROOT_ID,
+        storage::{N_TOTAL_REPLIES, OWNER, PENDING_OWNER, STATUS, TABLE, TOTAL_TIP_AMOUNTS},
+        views::ConfigView,
     },
 };
 
-use super::ReadonlyContext;
+use super::{
+    nodes::{is_moderator, load_node_view},
+    ReadonlyContext,
+};
 
 pub fn query_thread_info(
     ctx: ReadonlyContext,
@@ -35,9 +39,11 @@ pub fn query_thread_info(
     Ok(ThreadInfoResponse {
         n_total_replies: N_TOTAL_REPLIES.load(deps.storage)?,
         tips: tip_token_amounts,
-        root: NodeView::load(deps.storage, 0, &sender)?,
+        root: load_node_view(deps, ROOT_ID, &sender, is_moderator(deps, &sender)?)?,
         table: table_info,
+        status: STATUS.load(deps.storage)?,
         config,
         owner,
+        pending_owner: PENDING_OWNER.may_load(deps.storage)?,
     })
 }
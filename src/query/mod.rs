@@ -1,5 +1,9 @@
+pub mod bans;
+pub mod config;
 pub mod info;
 pub mod nodes;
+pub mod permissions;
+pub mod votes;
 
 use cosmwasm_std::{Deps, Env};
 
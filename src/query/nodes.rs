@@ -1,25 +1,105 @@
 use std::marker::PhantomData;
 
-use cosmwasm_std::{Addr, Order};
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Storage};
 use cw_storage_plus::Bound;
 
 use crate::{
     error::ContractError,
-    msg::{NodeViewByTagPaginationResponse, NodeViewRepliesPaginationResponse},
+    msg::{
+        ChildCursor, ChildOrder, FlaggedNodesPaginationResponse,
+        NodeViewByAuthorPaginationResponse, NodeViewByTagPaginationResponse,
+        NodeViewRepliesPaginationResponse, NodeViewSubtreePaginationResponse,
+        RecycledNodesPaginationResponse, RevisionsPaginationResponse, SavedNodesPaginationResponse,
+        SortOrder, TagFilter,
+    },
     state::{
-        storage::{IX_MENTION_NODE, IX_RANKED_CHILD, IX_TAG_NODE},
-        views::NodeView,
+        permissions::{is_authorized, Action},
+        storage::{
+            IX_ADDR_SAVED_AT, IX_AUTHOR_NODE, IX_BEST_CHILD, IX_CHILD, IX_CONTROVERSIAL_CHILD,
+            IX_FLAGGED, IX_HOT_CHILD, IX_MENTION_NODE, IX_NODE_TAG, IX_RANKED_CHILD,
+            IX_RECYCLE_BIN_DELETED_AT, IX_TAG_NODE, MAX_TAGS, NODE_ID_2_REVISION,
+            NODE_ID_2_TOMBSTONE, NODE_ID_ADDR_2_SAVED_AT, RECYCLE_BIN,
+        },
+        views::{FlaggedNodeView, NodeView, SavedNodeView},
     },
-    util::load_node_metadata,
+    util::{find_pending_deletion, load_node_metadata},
 };
 
 use super::ReadonlyContext;
 
 pub const DEFAULT_PAGINATION_LIMIT: u8 = 25;
+pub const MAX_SUBTREE_NODES: u8 = 100;
+/// Max number of index entries a filtered query examines per page.
+pub const MAX_SCANNED_IDS: usize = 500;
 
-pub enum TagWrapper {
-    Tag(String),
-    Mention(String),
+/// Takes up to `page_size` entries that pass `keep` from an index scan,
+/// examining at most MAX_SCANNED_IDS entries so that sparse matches can't run
+/// a query out of gas. Also returns the last entry examined if the page filled
+/// up or the cap was hit, which is where the next page resumes. A page may
+/// therefore hold fewer entries than requested, or none, and still have one.
+pub fn take_page<T: Clone>(
+    entries: impl Iterator<Item = StdResult<T>>,
+    page_size: usize,
+    mut keep: impl FnMut(&T) -> bool,
+) -> StdResult<(Vec<T>, Option<T>)> {
+    let mut page: Vec<T> = Vec::with_capacity(page_size);
+    if page_size == 0 {
+        return Ok((page, None));
+    }
+    for (i, result) in entries.enumerate() {
+        let entry = result?;
+        if keep(&entry) {
+            page.push(entry.clone());
+        }
+        if page.len() == page_size || i + 1 == MAX_SCANNED_IDS {
+            return Ok((page, Some(entry)));
+        }
+    }
+    Ok((page, None))
+}
+
+/// Returns true if the sender may see the content of nodes that are hidden
+/// pending review.
+///
+/// Queries are unauthenticated, so anyone can pass a moderator's address as
+/// the sender. Redaction only keeps hidden content out of ordinary listings;
+/// it doesn't make it confidential, as contract storage is public anyway.
+pub fn is_moderator(
+    deps: Deps,
+    sender: &Option<Addr>,
+) -> Result<bool, ContractError> {
+    match sender {
+        Some(addr) => is_authorized(deps, addr, Action::Moderate, None),
+        None => Ok(false),
+    }
+}
+
+/// Loads a NodeView on behalf of the sender, redacting its content if the
+/// node is hidden pending review and the sender is neither its author nor a
+/// moderator (see is_moderator for why this isn't access control). Deleted nodes and nodes pending deletion are not found.
+pub fn load_node_view(
+    deps: Deps,
+    id: u32,
+    sender: &Option<Addr>,
+    is_moderator: bool,
+) -> Result<NodeView, ContractError> {
+    load_node_metadata(deps.storage, id, true)?;
+    let mut node = NodeView::load(deps.storage, id, sender)?;
+    if node.metadata.hidden_at.is_some()
+        && !is_moderator
+        && sender.as_ref() != Some(&node.metadata.created_by)
+    {
+        node.redact();
+    }
+    Ok(node)
+}
+
+/// Returns false if the node is pending deletion, for filtering index scans.
+pub fn is_visible(
+    deps: Deps,
+    id: u32,
+) -> bool {
+    !matches!(find_pending_deletion(deps.storage, id), Ok(Some(_)))
 }
 
 pub fn query_nodes_by_id(
@@ -28,61 +108,246 @@ pub fn query_nodes_by_id(
     sender: Option<Addr>,
 ) -> Result<Vec<NodeView>, ContractError> {
     let ReadonlyContext { deps, .. } = ctx;
+    let is_moderator = is_moderator(deps, &sender)?;
     let mut nodes: Vec<NodeView> = Vec::with_capacity(ids.len());
     for id in ids.iter() {
-        nodes.push(NodeView::load(deps.storage, *id, &sender)?);
+        nodes.push(load_node_view(deps, *id, &sender, is_moderator)?);
     }
     Ok(nodes)
 }
 
+/// Pages through a node's replies in the given order, which defaults to Top.
+/// The cursor must have been returned by a listing in the same order.
 pub fn query_child_nodes(
     ctx: ReadonlyContext,
     parent_id: u32,
-    cursor: Option<(u32, i32, u32)>,
+    order: Option<ChildOrder>,
+    cursor: Option<ChildCursor>,
     limit: Option<u8>,
     sender: Option<Addr>,
 ) -> Result<NodeViewRepliesPaginationResponse, ContractError> {
     let ReadonlyContext { deps, .. } = ctx;
+    let order = order.unwrap_or(ChildOrder::Top);
+
+    if let Some(cursor) = &cursor {
+        if cursor.order() != order {
+            return Err(ContractError::ValidationError {
+                reason: format!("Cursor is not valid for {:?} order", order),
+            });
+        }
+    }
+
+    let is_moderator = is_moderator(deps, &sender)?;
     let parent_metadata = load_node_metadata(deps.storage, parent_id, true)?.unwrap();
     let page_size = parent_metadata
         .n_replies
         .min(limit.unwrap_or(DEFAULT_PAGINATION_LIMIT) as u16)
         .min(DEFAULT_PAGINATION_LIMIT as u16) as usize;
 
-    let start = if let Some(cursor) = cursor {
-        Some(Bound::Exclusive((cursor, PhantomData)))
-    } else {
-        Some(Bound::Inclusive((
-            (parent_id, i32::MAX, u32::MAX),
-            PhantomData,
-        )))
-    };
-
-    let stop = Some(Bound::Exclusive((
-        (parent_id, i32::MIN, u32::MIN),
-        PhantomData,
-    )));
+    let keys = child_keys(deps.storage, parent_id, order, cursor, page_size)?;
 
     let mut replies: Vec<NodeView> = Vec::with_capacity(page_size);
-    let mut cursor: Option<(u32, i32, u32)> = None;
-
-    for result in IX_RANKED_CHILD
-        .keys(deps.storage, stop, start.clone(), Order::Descending)
-        .take(page_size)
-    {
-        let (parent_id, rank, child_id) = result?;
-        replies.push(NodeView::load(deps.storage, child_id, &sender)?);
-        if replies.len() == page_size {
-            cursor = Some((parent_id, rank, child_id))
-        }
+    for key in keys.iter() {
+        replies.push(load_node_view(deps, key.id(), &sender, is_moderator)?);
     }
 
     Ok(NodeViewRepliesPaginationResponse {
         nodes: replies,
-        cursor,
+        cursor: if keys.len() == page_size {
+            keys.last().cloned()
+        } else {
+            None
+        },
+    })
+}
+
+/// Returns the keys of up to `limit` of a node's replies in the given order,
+/// starting after the given key, which must be of the same order.
+fn child_keys(
+    store: &dyn Storage,
+    parent_id: u32,
+    order: ChildOrder,
+    after: Option<ChildCursor>,
+    limit: usize,
+) -> Result<Vec<ChildCursor>, ContractError> {
+    Ok(match order {
+        ChildOrder::Top => {
+            let stop = match after {
+                Some(ChildCursor::Top { rank, id }) => Some(Bound::exclusive((rank, id))),
+                _ => None,
+            };
+            IX_RANKED_CHILD
+                .sub_prefix(parent_id)
+                .keys(store, None, stop, Order::Descending)
+                .take(limit)
+                .map(|r| r.map(|(rank, id)| ChildCursor::Top { rank, id }))
+                .collect::<Result<Vec<_>, _>>()?
+        },
+        ChildOrder::Hot => {
+            let stop = match after {
+                Some(ChildCursor::Hot { hot, id }) => Some(Bound::exclusive((hot, id))),
+                _ => None,
+            };
+            IX_HOT_CHILD
+                .sub_prefix(parent_id)
+                .keys(store, None, stop, Order::Descending)
+                .take(limit)
+                .map(|r| r.map(|(hot, id)| ChildCursor::Hot { hot, id }))
+                .collect::<Result<Vec<_>, _>>()?
+        },
+        ChildOrder::Best => {
+            let stop = match after {
+                Some(ChildCursor::Best { score, id }) => Some(Bound::exclusive((score, id))),
+                _ => None,
+            };
+            IX_BEST_CHILD
+                .sub_prefix(parent_id)
+                .keys(store, None, stop, Order::Descending)
+                .take(limit)
+                .map(|r| r.map(|(score, id)| ChildCursor::Best { score, id }))
+                .collect::<Result<Vec<_>, _>>()?
+        },
+        ChildOrder::Newest => {
+            let stop = match after {
+                Some(ChildCursor::Newest { id }) => Some(Bound::exclusive(id)),
+                _ => None,
+            };
+            IX_CHILD
+                .prefix(parent_id)
+                .keys(store, None, stop, Order::Descending)
+                .take(limit)
+                .map(|r| r.map(|id| ChildCursor::Newest { id }))
+                .collect::<Result<Vec<_>, _>>()?
+        },
+        ChildOrder::Oldest => {
+            let start = match after {
+                Some(ChildCursor::Oldest { id }) => Some(Bound::exclusive(id)),
+                _ => None,
+            };
+            IX_CHILD
+                .prefix(parent_id)
+                .keys(store, start, None, Order::Ascending)
+                .take(limit)
+                .map(|r| r.map(|id| ChildCursor::Oldest { id }))
+                .collect::<Result<Vec<_>, _>>()?
+        },
+        ChildOrder::Controversial => {
+            let stop = match after {
+                Some(ChildCursor::Controversial { controversy, id }) => {
+                    Some(Bound::exclusive((controversy, id)))
+                },
+                _ => None,
+            };
+            IX_CONTROVERSIAL_CHILD
+                .sub_prefix(parent_id)
+                .keys(store, None, stop, Order::Descending)
+                .take(limit)
+                .map(|r| r.map(|(controversy, id)| ChildCursor::Controversial { controversy, id }))
+                .collect::<Result<Vec<_>, _>>()?
+        },
     })
 }
 
+/// Pages through a node's subtree in pre-order: the node itself, followed by
+/// each of its replies in the given order, each followed in turn by its own
+/// replies, down to max_depth levels below the node. Clients can nest the
+/// listing by each node's metadata.depth. The cursor is the path of child
+/// keys from the node to the last node returned.
+pub fn query_subtree(
+    ctx: ReadonlyContext,
+    id: u32,
+    max_depth: Option<u8>,
+    max_nodes: Option<u8>,
+    order: Option<ChildOrder>,
+    cursor: Option<Vec<ChildCursor>>,
+    sender: Option<Addr>,
+) -> Result<NodeViewSubtreePaginationResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let order = order.unwrap_or(ChildOrder::Top);
+    let max_depth = max_depth.unwrap_or(u8::MAX) as usize;
+    let max_nodes = max_nodes
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .clamp(1, MAX_SUBTREE_NODES) as usize;
+    let is_moderator = is_moderator(deps, &sender)?;
+
+    load_node_metadata(deps.storage, id, true)?;
+
+    let mut nodes: Vec<NodeView> = Vec::with_capacity(max_nodes);
+    let mut path: Vec<ChildCursor> = vec![];
+    // Whether to visit the replies of the last node in the path next
+    let mut descend = true;
+
+    match cursor {
+        None => nodes.push(load_node_view(deps, id, &sender, is_moderator)?),
+        Some(cursor) => {
+            for key in cursor {
+                if key.order() != order {
+                    return Err(ContractError::ValidationError {
+                        reason: format!("Cursor is not valid for {:?} order", order),
+                    });
+                }
+                let key_id = key.id();
+                path.push(key);
+                // Skip nodes deleted since the previous page, and their replies
+                if load_node_metadata(deps.storage, key_id, false)?.is_none() {
+                    descend = false;
+                    break;
+                }
+            }
+        },
+    }
+
+    let mut is_complete = false;
+
+    while nodes.len() < max_nodes {
+        let first_child = if descend && path.len() < max_depth {
+            let parent_id = path.last().map_or(id, |key| key.id());
+            child_keys(deps.storage, parent_id, order, None, 1)?.pop()
+        } else {
+            None
+        };
+        descend = true;
+
+        let next = match first_child {
+            Some(key) => Some(key),
+            None => next_sibling(deps.storage, id, order, &mut path)?,
+        };
+
+        match next {
+            Some(key) => {
+                nodes.push(load_node_view(deps, key.id(), &sender, is_moderator)?);
+                path.push(key);
+            },
+            None => {
+                is_complete = true;
+                break;
+            },
+        }
+    }
+
+    Ok(NodeViewSubtreePaginationResponse {
+        nodes,
+        cursor: if is_complete { None } else { Some(path) },
+    })
+}
+
+/// Pops the path up to the nearest node that has a next sibling, returning
+/// that sibling's key, or None once the whole subtree has been visited.
+fn next_sibling(
+    store: &dyn Storage,
+    root_id: u32,
+    order: ChildOrder,
+    path: &mut Vec<ChildCursor>,
+) -> Result<Option<ChildCursor>, ContractError> {
+    while let Some(key) = path.pop() {
+        let parent_id = path.last().map_or(root_id, |parent| parent.id());
+        if let Some(sibling) = child_keys(store, parent_id, order, Some(key), 1)?.pop() {
+            return Ok(Some(sibling));
+        }
+    }
+    Ok(None)
+}
+
 pub fn query_ancestor_nodes(
     ctx: ReadonlyContext,
     start_node_id: u32,
@@ -91,6 +356,7 @@ pub fn query_ancestor_nodes(
 ) -> Result<Vec<NodeView>, ContractError> {
     let ReadonlyContext { deps, .. } = ctx;
     let levels = levels.unwrap_or(1);
+    let is_moderator = is_moderator(deps, &sender)?;
     let mut nodes: Vec<NodeView> = Vec::with_capacity(levels as usize);
 
     let start_node_metadata = load_node_metadata(deps.storage, start_node_id, true)?.unwrap();
@@ -98,8 +364,8 @@ pub fn query_ancestor_nodes(
 
     for _ in 0..levels {
         if let Some(parent_id) = maybe_parent_id {
-            let node = NodeView::load(deps.storage, parent_id, &sender)?;
-            maybe_parent_id = node.metadata.parent_id.clone();
+            let node = load_node_view(deps, parent_id, &sender, is_moderator)?;
+            maybe_parent_id = node.metadata.parent_id;
             nodes.push(node);
         } else {
             break;
@@ -109,34 +375,311 @@ pub fn query_ancestor_nodes(
     Ok(nodes)
 }
 
+/// Pages through the nodes matching a tag filter and/or a mention, newest
+/// first by default. Without a mention, the first tag's index drives the scan
+/// for AllOf filters, while AnyOf filters merge the indices of all their tags.
+///
+/// Each candidate from the driving index costs a lookup per tag, so a page
+/// examines at most MAX_SCANNED_IDS candidates. When a rare combination runs
+/// into that cap, the page holds fewer nodes than requested, possibly none,
+/// and the cursor resumes the scan where it stopped.
 pub fn query_nodes_by_tag_or_mention(
     ctx: ReadonlyContext,
-    wrapped_tag: TagWrapper,
+    tags: Option<TagFilter>,
+    mention: Option<String>,
+    order: Option<SortOrder>,
     cursor: Option<u32>,
+    limit: Option<u8>,
     sender: Option<Addr>,
 ) -> Result<NodeViewByTagPaginationResponse, ContractError> {
     let ReadonlyContext { deps, .. } = ctx;
-    let mut nodes: Vec<NodeView> = Vec::with_capacity(8);
-    let start = if let Some(cursor_node_id) = cursor {
-        Some(Bound::Exclusive((cursor_node_id, PhantomData)))
+    let is_moderator = is_moderator(deps, &sender)?;
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let order: Order = order.unwrap_or(SortOrder::Descending).into();
+    let (min, max) = match order {
+        Order::Ascending => (cursor.map(Bound::exclusive), None),
+        Order::Descending => (None, cursor.map(Bound::exclusive)),
+    };
+
+    if tags.is_none() && mention.is_none() {
+        return Err(ContractError::ValidationError {
+            reason: "Either tags or a mention is required".to_owned(),
+        });
+    }
+    if let Some(TagFilter::AllOf(tags) | TagFilter::AnyOf(tags)) = &tags {
+        if tags.is_empty() || tags.len() > MAX_TAGS {
+            return Err(ContractError::ValidationError {
+                reason: format!("Tag filters must have between 1 and {} tags", MAX_TAGS),
+            });
+        }
+    }
+    let (match_all, tags) = match tags {
+        Some(TagFilter::AllOf(tags)) => (true, tags),
+        Some(TagFilter::AnyOf(tags)) => (false, tags),
+        None => (true, vec![]),
+    };
+    let tags: Vec<String> = tags.iter().map(|tag| tag.to_lowercase()).collect();
+    let mention = mention.map(|mention| mention.to_lowercase());
+
+    let store = deps.storage;
+    let candidates = if let Some(mention) = &mention {
+        IX_MENTION_NODE.prefix(mention).keys(store, min, max, order)
+    } else if match_all {
+        IX_TAG_NODE.prefix(&tags[0]).keys(store, min, max, order)
     } else {
-        None
+        merge_ids(
+            tags.iter()
+                .map(|tag| {
+                    IX_TAG_NODE
+                        .prefix(tag)
+                        .keys(store, min.clone(), max.clone(), order)
+                })
+                .collect(),
+            order,
+        )
     };
-    let (map, tag) = match wrapped_tag {
-        TagWrapper::Tag(s) => (IX_TAG_NODE, s.to_lowercase()),
-        TagWrapper::Mention(s) => (IX_MENTION_NODE, s.to_lowercase()),
+    let has_tag = |id: u32, tag: &String| IX_NODE_TAG.has(store, (id, tag));
+    let is_match = |id: u32| {
+        if match_all {
+            tags.iter().all(|tag| has_tag(id, tag))
+        } else {
+            tags.iter().any(|tag| has_tag(id, tag))
+        }
     };
 
-    for result in map
-        .prefix(&tag)
-        .keys(deps.storage, None, start, Order::Descending)
-        .take(DEFAULT_PAGINATION_LIMIT as usize)
+    let (ids, cursor) = take_page(candidates, page_size, |id| {
+        is_match(*id) && is_visible(deps, *id)
+    })?;
+
+    let mut nodes: Vec<NodeView> = Vec::with_capacity(ids.len());
+    for id in ids.iter() {
+        nodes.push(load_node_view(deps, *id, &sender, is_moderator)?);
+    }
+
+    Ok(NodeViewByTagPaginationResponse { nodes, cursor })
+}
+
+/// Merges id iterators that are each sorted in the given order into one,
+/// yielding ids found by more than one of them only once.
+fn merge_ids<'a>(
+    iters: Vec<Box<dyn Iterator<Item = StdResult<u32>> + 'a>>,
+    order: Order,
+) -> Box<dyn Iterator<Item = StdResult<u32>> + 'a> {
+    let mut iters: Vec<_> = iters.into_iter().map(Iterator::peekable).collect();
+    Box::new(std::iter::from_fn(move || {
+        let mut next: Option<u32> = None;
+        for iter in iters.iter_mut() {
+            let id = match iter.peek().map(|r| r.as_ref().ok().copied()) {
+                Some(Some(id)) => id,
+                Some(None) => return iter.next(),
+                None => continue,
+            };
+            let is_next = next.is_none_or(|next| match order {
+                Order::Ascending => id < next,
+                Order::Descending => id > next,
+            });
+            if is_next {
+                next = Some(id);
+            }
+        }
+        let id = next?;
+        for iter in iters.iter_mut() {
+            iter.next_if(|r| matches!(r, Ok(peeked) if *peeked == id));
+        }
+        Some(Ok(id))
+    }))
+}
+
+/// Pages through the nodes created by an address, newest first, e.g. for a
+/// user's post history. Soft deleted nodes are left out, and like other
+/// filtered queries, a page examines at most MAX_SCANNED_IDS of the address's
+/// nodes (see take_page).
+pub fn query_nodes_by_author(
+    ctx: ReadonlyContext,
+    address: Addr,
+    cursor: Option<u32>,
+    limit: Option<u8>,
+    sender: Option<Addr>,
+) -> Result<NodeViewByAuthorPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let is_moderator = is_moderator(deps, &sender)?;
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let stop = cursor.map(Bound::exclusive);
+
+    let (ids, cursor) = take_page(
+        IX_AUTHOR_NODE
+            .prefix(&address)
+            .keys(deps.storage, None, stop, Order::Descending),
+        page_size,
+        |id| is_visible(deps, *id) && !NODE_ID_2_TOMBSTONE.has(deps.storage, *id),
+    )?;
+
+    let mut nodes: Vec<NodeView> = Vec::with_capacity(ids.len());
+    for id in ids.iter() {
+        nodes.push(load_node_view(deps, *id, &sender, is_moderator)?);
+    }
+
+    Ok(NodeViewByAuthorPaginationResponse { nodes, cursor })
+}
+
+/// Pages through the nodes saved by an address, most recently saved first.
+/// Nodes are loaded on behalf of the address. A page examines at most
+/// MAX_SCANNED_IDS saves (see take_page).
+pub fn query_saved_nodes(
+    ctx: ReadonlyContext,
+    address: Addr,
+    cursor: Option<(u64, u32)>,
+    limit: Option<u8>,
+) -> Result<SavedNodesPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let sender = Some(address.clone());
+    let is_moderator = is_moderator(deps, &sender)?;
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let stop = cursor.map(Bound::exclusive);
+
+    let (keys, cursor) = take_page(
+        IX_ADDR_SAVED_AT
+            .sub_prefix(&address)
+            .keys(deps.storage, None, stop, Order::Descending),
+        page_size,
+        |(_, id)| is_visible(deps, *id),
+    )?;
+
+    let mut nodes: Vec<SavedNodeView> = Vec::with_capacity(keys.len());
+    for (_, id) in keys.iter() {
+        nodes.push(SavedNodeView {
+            node: load_node_view(deps, *id, &sender, is_moderator)?,
+            saved_at: NODE_ID_ADDR_2_SAVED_AT.load(deps.storage, (*id, &address))?,
+        });
+    }
+
+    Ok(SavedNodesPaginationResponse { nodes, cursor })
+}
+
+/// Pages through the moderation queue, from the most to the least flagged
+/// node. Nodes with the same number of flags are returned newest first. A
+/// page examines at most MAX_SCANNED_IDS queue entries (see take_page).
+pub fn query_flagged_nodes(
+    ctx: ReadonlyContext,
+    cursor: Option<(u8, u32)>,
+    limit: Option<u8>,
+    sender: Option<Addr>,
+) -> Result<FlaggedNodesPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let is_moderator = is_moderator(deps, &sender)?;
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let stop = cursor.map(|c| Bound::Exclusive((c, PhantomData)));
+
+    let (keys, cursor) = take_page(
+        IX_FLAGGED.keys(deps.storage, None, stop, Order::Descending),
+        page_size,
+        |(_, id)| is_visible(deps, *id),
+    )?;
+
+    let mut nodes: Vec<FlaggedNodeView> = Vec::with_capacity(keys.len());
+    for (_, node_id) in keys {
+        nodes.push(FlaggedNodeView {
+            node: load_node_view(deps, node_id, &sender, is_moderator)?,
+            flags: FlaggedNodeView::load_flags(deps.storage, node_id)?,
+        });
+    }
+
+    Ok(FlaggedNodesPaginationResponse { nodes, cursor })
+}
+
+/// Pages through a node's edit history, newest revision first. The history of
+/// a node hidden pending review is only visible to its author and moderators.
+pub fn query_revisions(
+    ctx: ReadonlyContext,
+    id: u32,
+    cursor: Option<u16>,
+    limit: Option<u8>,
+    sender: Option<Addr>,
+) -> Result<RevisionsPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let metadata = load_node_metadata(deps.storage, id, true)?.unwrap();
+
+    if metadata.hidden_at.is_some()
+        && sender.as_ref() != Some(&metadata.created_by)
+        && !is_moderator(deps, &sender)?
     {
-        let node_id = result?;
-        nodes.push(NodeView::load(deps.storage, node_id, &sender)?);
+        return Ok(RevisionsPaginationResponse {
+            revisions: vec![],
+            cursor: None,
+        });
+    }
+
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let stop = cursor.map(Bound::exclusive);
+
+    let revisions = NODE_ID_2_REVISION
+        .prefix(id)
+        .range(deps.storage, None, stop, Order::Descending)
+        .take(page_size)
+        .map(|r| r.map(|(_, revision)| revision))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(RevisionsPaginationResponse {
+        cursor: if revisions.len() == page_size {
+            revisions.last().map(|r| r.revision)
+        } else {
+            None
+        },
+        revisions,
+    })
+}
+
+/// Pages through the recycle bin, most recently deleted nodes first, skipping
+/// expired entries. Only the owner and moderators may browse deleted content here.
+///
+/// As with is_moderator, this is a convenience for clients rather than access
+/// control: the sender isn't authenticated, and recycled content can be read
+/// from contract storage by anyone until it expires.
+pub fn query_deleted_nodes(
+    ctx: ReadonlyContext,
+    cursor: Option<(u64, u32)>,
+    limit: Option<u8>,
+    sender: Option<Addr>,
+) -> Result<RecycledNodesPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, env } = ctx;
+
+    if !is_moderator(deps, &sender)? {
+        return Err(ContractError::NotAuthorized {
+            reason: "Only moderators can view deleted nodes".to_owned(),
+        });
     }
-    Ok(NodeViewByTagPaginationResponse {
-        cursor: nodes.last().map(|u| u.metadata.id),
+
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let stop = cursor.map(Bound::exclusive);
+    let now = env.block.time;
+
+    let nodes = IX_RECYCLE_BIN_DELETED_AT
+        .keys(deps.storage, None, stop, Order::Descending)
+        .map(|r| r.and_then(|(_, id)| RECYCLE_BIN.load(deps.storage, id)))
+        .filter(|r| r.as_ref().map_or(true, |node| node.expires_at > now))
+        .take(page_size)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(RecycledNodesPaginationResponse {
+        cursor: if nodes.len() == page_size {
+            nodes
+                .last()
+                .map(|node| (node.deleted.deleted_at.seconds(), node.metadata.id))
+        } else {
+            None
+        },
         nodes,
     })
 }
@@ -0,0 +1,50 @@
+use cosmwasm_std::Addr;
+
+use crate::{
+    error::ContractError,
+    msg::PermissionsResponse,
+    state::permissions::{has_role, is_authorized, Action, Role},
+    util::load_node_metadata,
+};
+
+use super::ReadonlyContext;
+
+/// Reports the roles held by an address and the actions it may perform. If a
+/// node ID is given, the author role and author-scoped actions are evaluated
+/// with respect to that node; otherwise the address is treated as a
+/// non-author.
+pub fn query_permissions(
+    ctx: ReadonlyContext,
+    address: Addr,
+    id: Option<u32>,
+) -> Result<PermissionsResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+
+    let mut author: Option<Addr> = None;
+    if let Some(id) = id {
+        let node = load_node_metadata(deps.storage, id, true)?.unwrap();
+        author = Some(node.created_by);
+    }
+
+    let mut actions: Vec<Action> = Vec::with_capacity(Action::ALL.len());
+    let mut roles: Vec<Role> = Vec::with_capacity(3);
+
+    for action in Action::ALL {
+        if is_authorized(deps, &address, action, author.as_ref())? {
+            actions.push(action);
+        }
+        // An ACL owner may grant the owner role for some actions but not
+        // others, so roles are collected across all actions.
+        for role in action.roles() {
+            if !roles.contains(role) && has_role(deps, &address, *role, action, author.as_ref())? {
+                roles.push(*role);
+            }
+        }
+    }
+
+    Ok(PermissionsResponse {
+        address,
+        roles,
+        actions,
+    })
+}
@@ -0,0 +1,124 @@
+use cosmwasm_std::{Addr, Order, Storage};
+use cw_storage_plus::Bound;
+
+use crate::{
+    error::ContractError,
+    msg::{Sentiment, VoteView, VoterView, VotersPaginationResponse, VotesPaginationResponse},
+    state::storage::{ADDR_NODE_ID_2_SENTIMENT, CONFIG_PRIVATE_VOTES, NODE_ID_ADDR_2_SENTIMENT},
+    util::load_node_metadata,
+};
+
+use super::{
+    nodes::{is_visible, take_page, DEFAULT_PAGINATION_LIMIT},
+    ReadonlyContext,
+};
+
+/// Pages through the votes cast by an address, newest nodes first, so that
+/// clients can restore the address's vote state for many nodes at once.
+/// Optionally only includes votes of the given sentiment. A page examines at
+/// most MAX_SCANNED_IDS of the address's votes (see take_page).
+///
+/// Like NodeAccountView.sentiment, this stays available when votes are
+/// private: it only answers for an address the caller already knows, which
+/// clients need to show users their own votes.
+pub fn query_votes(
+    ctx: ReadonlyContext,
+    address: Addr,
+    sentiment_filter: Option<Sentiment>,
+    cursor: Option<u32>,
+    limit: Option<u8>,
+) -> Result<VotesPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let stop = cursor.map(Bound::exclusive);
+    let filter_u8 = sentiment_filter.map(|sentiment| sentiment.to_u8());
+
+    let (entries, last_scanned) = take_page(
+        ADDR_NODE_ID_2_SENTIMENT.prefix(&address).range(
+            deps.storage,
+            None,
+            stop,
+            Order::Descending,
+        ),
+        page_size,
+        |(id, sentiment_u8)| filter_u8.is_none_or(|f| f == *sentiment_u8) && is_visible(deps, *id),
+    )?;
+
+    let votes = entries
+        .into_iter()
+        .map(|(node_id, sentiment_u8)| VoteView {
+            node_id,
+            sentiment: Sentiment::from_u8(sentiment_u8).unwrap(),
+        })
+        .collect();
+
+    Ok(VotesPaginationResponse {
+        cursor: last_scanned.map(|(node_id, _)| node_id),
+        votes,
+    })
+}
+
+/// Pages through the addresses that voted on a node in address order,
+/// optionally only those who voted with the given sentiment.
+pub fn query_voters(
+    ctx: ReadonlyContext,
+    id: u32,
+    sentiment: Option<Sentiment>,
+    cursor: Option<Addr>,
+    limit: Option<u8>,
+) -> Result<VotersPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    ensure_votes_public(deps.storage)?;
+
+    // Ensure the node exists
+    load_node_metadata(deps.storage, id, true)?;
+
+    let page_size = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(DEFAULT_PAGINATION_LIMIT) as usize;
+    let start = cursor.as_ref().map(Bound::exclusive);
+    let filter_u8 = sentiment.map(|sentiment| sentiment.to_u8());
+
+    let voters = NODE_ID_ADDR_2_SENTIMENT
+        .prefix(id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|r| {
+            r.as_ref().map_or(true, |(_, sentiment_u8)| {
+                filter_u8.is_none_or(|f| f == *sentiment_u8)
+            })
+        })
+        .take(page_size)
+        .map(|r| {
+            r.map(|(address, sentiment_u8)| VoterView {
+                address,
+                sentiment: Sentiment::from_u8(sentiment_u8).unwrap(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(VotersPaginationResponse {
+        cursor: if voters.len() == page_size {
+            voters.last().map(|voter| voter.address.clone())
+        } else {
+            None
+        },
+        voters,
+    })
+}
+
+/// Errors if the thread is configured to keep voters private, in which case
+/// nodes' voters can't be listed.
+///
+/// This is privacy at the level of the contract's API only. Votes are kept in
+/// public contract storage, where anyone can read them with raw queries.
+fn ensure_votes_public(store: &dyn Storage) -> Result<(), ContractError> {
+    if CONFIG_PRIVATE_VOTES.may_load(store)?.unwrap_or(false) {
+        return Err(ContractError::NotAuthorized {
+            reason: "Votes are private in this thread".to_owned(),
+        });
+    }
+    Ok(())
+}
@@ -1,9 +1,9 @@
 pub mod models;
+pub mod permissions;
 pub mod storage;
 pub mod views;
 
-use cosmwasm_std::{Addr, DepsMut, Response};
-use cw_acl::client::Acl;
+use cosmwasm_std::{Addr, Response, Storage, Timestamp};
 use cw_lib::models::Owner;
 
 use crate::{
@@ -11,14 +11,19 @@ use crate::{
     execute::Context,
     msg::InstantiateMsg,
     util::process_tags_and_mentions,
-    validation::{validate_body, validate_mentions, validate_sections, validate_tags, validate_title},
+    validation::{
+        validate_body, validate_edit_policy, validate_flag_threshold, validate_limit_overrides,
+        validate_mentions, validate_sections, validate_tags, validate_title,
+    },
 };
 
 use self::{
-    models::{NodeMetadata, ROOT_ID},
+    models::{Limits, NodeMetadata, ThreadStatus, ROOT_ID},
     storage::{
-        ACTIVITY_SCORE, CONFIG_TIP_TOKEN_ALLOWLIST, NODE_ID_2_BODY, NODE_ID_2_METADATA,
-        NODE_ID_2_SECTION, NODE_ID_2_TITLE, NODE_ID_COUNTER, N_TOTAL_REPLIES, OWNER,
+        ACTIVITY_SCORE, BANS, CONFIG_EDIT_POLICY, CONFIG_FLAG_THRESHOLD, CONFIG_LIMITS,
+        CONFIG_PRIVATE_VOTES, CONFIG_RECYCLE_BIN_RETENTION, CONFIG_TIP_TOKEN_ALLOWLIST,
+        IX_AUTHOR_NODE, MIGRATION, NODE_ID_2_BODY, NODE_ID_2_METADATA, NODE_ID_2_SECTION,
+        NODE_ID_2_TITLE, NODE_ID_COUNTER, N_TOTAL_REPLIES, OWNER, PURGE_PENDING, STATUS,
         TIP_TOKEN_LUTAB,
     },
 };
@@ -34,20 +39,41 @@ pub fn init(
         deps.api.addr_validate(owner.to_addr().as_str())?;
     }
 
-    // Validate all input
+    // Validate all input against the thread's own limits
+    let mut limits = Limits::default();
+    if let Some(overrides) = &msg.config.limits {
+        validate_limit_overrides(overrides)?;
+        CONFIG_LIMITS.save(deps.storage, overrides)?;
+        limits = limits.with_overrides(overrides);
+    }
     if let Some(ref title) = msg.title {
-        validate_title(title)?;
+        validate_title(title, &limits)?;
     }
     if let Some(ref body) = msg.body {
-        validate_body(body)?;
+        validate_body(body, &limits)?;
     }
-    validate_tags(&msg.tags)?;
-    validate_mentions(&msg.mentions)?;
-    validate_sections(&msg.sections)?;
+    validate_tags(&msg.tags, &limits)?;
+    validate_mentions(&msg.mentions, &limits)?;
+    validate_sections(&msg.sections, &limits)?;
 
     CONFIG_TIP_TOKEN_ALLOWLIST.save(deps.storage, &msg.config.tip_tokens)?;
+    if let Some(threshold) = &msg.config.flag_threshold {
+        validate_flag_threshold(threshold)?;
+        CONFIG_FLAG_THRESHOLD.save(deps.storage, threshold)?;
+    }
+    if let Some(policy) = &msg.config.edit_policy {
+        validate_edit_policy(policy)?;
+        CONFIG_EDIT_POLICY.save(deps.storage, policy)?;
+    }
+    if let Some(retention) = msg.config.recycle_bin_retention_seconds {
+        CONFIG_RECYCLE_BIN_RETENTION.save(deps.storage, &retention)?;
+    }
+    if let Some(private_votes) = msg.config.private_votes {
+        CONFIG_PRIVATE_VOTES.save(deps.storage, &private_votes)?;
+    }
     ACTIVITY_SCORE.save(deps.storage, &0)?;
     N_TOTAL_REPLIES.save(deps.storage, &0)?;
+    STATUS.save(deps.storage, &ThreadStatus::Active)?;
 
     for token in msg.config.tip_tokens.iter() {
         TIP_TOKEN_LUTAB.save(deps.storage, &token.get_key(), &true)?;
@@ -70,7 +96,7 @@ pub fn init(
     // Save sections
     let mut n_sections: u8 = 0;
     for (i, section) in msg.sections.unwrap_or_default().iter().enumerate() {
-        NODE_ID_2_SECTION.save(deps.storage, (ROOT_ID, i as u8), &section)?;
+        NODE_ID_2_SECTION.save(deps.storage, (ROOT_ID, i as u8), section)?;
         n_sections += 1;
     }
 
@@ -88,38 +114,74 @@ pub fn init(
             n_replies: 0,
             n_flags: 0,
             depth: 0,
+            hidden_at: None,
+            n_revisions: 0,
+            n_upvotes: 0,
+            n_downvotes: 0,
         },
     )?;
 
+    IX_AUTHOR_NODE.save(deps.storage, (&info.sender, ROOT_ID), &true)?;
+
     process_tags_and_mentions(deps.storage, ROOT_ID, msg.tags, msg.mentions, false)?;
 
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
-pub fn authorize_action(
-    deps: &DepsMut,
-    principal: &Addr,
-    action: &str,
+/// Aborts unless the thread is active, i.e. neither suspended by its table
+/// nor torn down nor being deleted, and no state migration is in progress.
+pub fn ensure_active(store: &dyn Storage) -> Result<(), ContractError> {
+    ensure_not_purging(store)?;
+    ensure_not_migrating(store)?;
+    match STATUS.load(store)? {
+        ThreadStatus::Active => Ok(()),
+        status => Err(ContractError::ThreadNotActive { status }),
+    }
+}
+
+/// Aborts if the thread has been torn down and is now read-only, if it is
+/// being deleted, or if a state migration is in progress.
+pub fn ensure_not_terminated(store: &dyn Storage) -> Result<(), ContractError> {
+    ensure_not_purging(store)?;
+    ensure_not_migrating(store)?;
+    match STATUS.load(store)? {
+        ThreadStatus::Terminated => Err(ContractError::ThreadNotActive {
+            status: ThreadStatus::Terminated,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Aborts if the given address is currently banned from the thread.
+pub fn ensure_not_banned(
+    store: &dyn Storage,
+    addr: &Addr,
+    time: Timestamp,
 ) -> Result<(), ContractError> {
-    if !is_action_authorized(deps, principal, action)? {
-        Err(ContractError::NotAuthorized {
-            reason: "Owner authorization required".to_owned(),
-        })
-    } else {
-        Ok(())
+    if let Some(ban) = BANS.may_load(store, addr)? {
+        if ban.is_active(time) {
+            return Err(ContractError::Banned {
+                address: addr.to_string(),
+            });
+        }
     }
+    Ok(())
 }
 
-pub fn is_action_authorized(
-    deps: &DepsMut,
-    principal: &Addr,
-    action: &str,
-) -> Result<bool, ContractError> {
-    Ok(match OWNER.load(deps.storage)? {
-        Owner::Address(addr) => *principal == addr,
-        Owner::Acl(acl_addr) => {
-            let acl = Acl::new(&acl_addr);
-            acl.is_allowed(&deps.querier, principal, action)?
-        },
-    })
+/// Aborts while a batched state migration is still in progress, as indexes
+/// may be incomplete until it finishes.
+pub fn ensure_not_migrating(store: &dyn Storage) -> Result<(), ContractError> {
+    if MIGRATION.may_load(store)?.is_some() {
+        return Err(ContractError::MigrationPending {});
+    }
+    Ok(())
+}
+
+/// Aborts once the root node has been deleted and the thread's state is being
+/// purged, as any state may already be gone.
+pub fn ensure_not_purging(store: &dyn Storage) -> Result<(), ContractError> {
+    if PURGE_PENDING.exists(store) {
+        return Err(ContractError::ThreadDeleted {});
+    }
+    Ok(())
 }
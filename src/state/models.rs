@@ -1,10 +1,15 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Storage, Timestamp};
+use cosmwasm_std::{Addr, Binary, Decimal, Storage, Timestamp, Uint128};
+use cw_lib::models::Owner;
 use cw_table::client::Table;
 
 use crate::error::ContractError;
 
-use super::storage::TABLE;
+use super::permissions::Role;
+use super::storage::{
+    CONFIG_LIMITS, MAX_BODY_LENGTH, MAX_MENTIONS, MAX_REVISIONS, MAX_SECTIONS, MAX_TAGS,
+    MAX_TAG_LENGTH, MAX_TIP_TOKEN_TYPES, MAX_TITLE_LENGTH, TABLE,
+};
 
 pub const NIL: u8 = 0;
 pub const DOWN: u8 = 1;
@@ -12,6 +17,23 @@ pub const UP: u8 = 2;
 
 pub const ROOT_ID: u32 = 0;
 
+/// Fixed-point scale of hot scores. A reply's hot score rises by one unit for
+/// each tenfold increase in its net votes or HOT_DECAY_SECONDS of recency.
+pub const HOT_SCALE: i64 = 1 << 16;
+pub const HOT_DECAY_SECONDS: i64 = 45_000;
+
+/// Wilson scores are expressed in parts per million.
+pub const WILSON_SCALE: u128 = 1_000_000;
+/// Standard score of the Wilson score interval, for 95% confidence.
+const WILSON_Z_PERMILLE: u64 = 1_960;
+
+#[cw_serde]
+pub enum ThreadStatus {
+    Active,
+    Suspended,
+    Terminated,
+}
+
 #[cw_serde]
 pub struct TableMetadata {
     pub address: Addr,
@@ -64,6 +86,310 @@ pub struct NodeMetadata {
     pub n_sections: u8,
     pub n_flags: u8,
     pub depth: u8,
+    pub hidden_at: Option<Timestamp>,
+    /// Number of times the node has been edited.
+    #[serde(default)]
+    pub n_revisions: u16,
+    /// Number of upvotes and downvotes received. Rank is their difference.
+    #[serde(default)]
+    pub n_upvotes: u32,
+    #[serde(default)]
+    pub n_downvotes: u32,
+}
+
+impl NodeMetadata {
+    /// Measures how heavily and evenly the node is voted on: its total number
+    /// of votes, weighted by the ratio of its minority to majority votes.
+    /// Nodes without both up and downvotes aren't controversial at all.
+    pub fn controversy(&self) -> u32 {
+        let n_min = self.n_upvotes.min(self.n_downvotes) as u64;
+        let n_max = self.n_upvotes.max(self.n_downvotes) as u64;
+        if n_min == 0 {
+            return 0;
+        }
+        let n_votes = self.n_upvotes as u64 + self.n_downvotes as u64;
+        (n_votes * n_min / n_max).min(u32::MAX as u64) as u32
+    }
+
+    /// Ranks the node by net votes decayed over time, so that newer replies
+    /// can overtake older ones with more votes: log10 of the net votes, signed,
+    /// plus the creation time in units of HOT_DECAY_SECONDS.
+    pub fn hot(&self) -> i64 {
+        let order = log10_fixed(self.rank.unsigned_abs().max(1));
+        let recency = self.created_at.seconds() as i64 * HOT_SCALE / HOT_DECAY_SECONDS;
+        self.rank.signum() as i64 * order + recency
+    }
+
+    /// Ranks the node by the lower bound of the Wilson score interval of its
+    /// share of upvotes, i.e. the share it can be said to deserve with 95%
+    /// confidence, so that a few unanimous votes don't beat many mostly
+    /// positive ones. Nodes without votes score zero.
+    pub fn wilson_score(&self) -> u32 {
+        let n_votes = self.n_upvotes as u128 + self.n_downvotes as u128;
+        if n_votes == 0 {
+            return 0;
+        }
+        let n = Decimal::from_ratio(n_votes, 1u128);
+        let p = Decimal::from_ratio(self.n_upvotes, n_votes);
+        let z = Decimal::permille(WILSON_Z_PERMILLE);
+        let z2 = z * z;
+        let center = p + z2 / (n + n);
+        let variance = p * (Decimal::one() - p) / n + z2 / (n * Decimal::percent(400)) / n;
+        let spread = z * variance.sqrt();
+        let lower = center.saturating_sub(spread) / (Decimal::one() + z2 / n);
+        (Uint128::new(WILSON_SCALE) * lower).u128() as u32
+    }
+}
+
+/// Computes log10(n) scaled by HOT_SCALE, using integer arithmetic only.
+fn log10_fixed(n: u32) -> i64 {
+    // Integral part of log2(n), then each fractional bit by repeated squaring
+    // of n normalized to [1, 2), with 32 fractional bits.
+    let int_part = 31 - n.leading_zeros() as i64;
+    let mut y = ((n as u128) << 32) >> int_part;
+    let mut log2 = int_part << 16;
+    for bit in (0..16).rev() {
+        y = (y * y) >> 32;
+        if y >= 2 << 32 {
+            y >>= 1;
+            log2 |= 1 << bit;
+        }
+    }
+    // log10(n) = log2(n) * log10(2)
+    log2 * 30_103 / 100_000
+}
+
+/// Marks a node whose content was removed by a soft delete. The node itself is
+/// kept so that its replies remain in place.
+#[cw_serde]
+pub struct Tombstone {
+    pub deleted_at: Timestamp,
+    pub deleted_by: Addr,
+    /// Role under which the node was deleted, i.e. by its author or a moderator.
+    pub role: Role,
+}
+
+/// A ban purge whose author's nodes haven't all been scheduled for deletion
+/// yet. The cursor is the last node ID visited.
+#[cw_serde]
+pub struct AuthorPurge {
+    pub tombstone: Tombstone,
+    pub cursor: Option<u32>,
+}
+
+/// Content of a deleted node, kept in the recycle bin until it expires so that
+/// the node can be restored.
+#[cw_serde]
+pub struct RecycledNode {
+    /// The node's metadata at the time it was deleted.
+    pub metadata: NodeMetadata,
+    pub title: Option<String>,
+    pub body: String,
+    pub sections: Vec<Section>,
+    pub tags: Vec<String>,
+    pub mentions: Vec<String>,
+    pub deleted: Tombstone,
+    pub expires_at: Timestamp,
+}
+
+/// Determines when a node is automatically hidden pending moderator review.
+/// A node is hidden once it has at least `count` flags and, if `ratio` is set,
+/// its ratio of flags to upvotes is at least `ratio`.
+#[cw_serde]
+pub struct FlagThreshold {
+    pub count: u8,
+    pub ratio: Option<Decimal>,
+}
+
+impl FlagThreshold {
+    pub fn is_exceeded(
+        &self,
+        metadata: &NodeMetadata,
+    ) -> bool {
+        if metadata.n_flags < self.count {
+            return false;
+        }
+        if let Some(ratio) = self.ratio {
+            let n_upvotes = metadata.n_upvotes.max(1);
+            Decimal::from_ratio(metadata.n_flags as u32, n_upvotes) >= ratio
+        } else {
+            true
+        }
+    }
+}
+
+/// Content limits enforced when creating or editing nodes, reported to clients
+/// so they can validate input before submitting it.
+#[cw_serde]
+pub struct Limits {
+    pub max_title_length: u32,
+    pub max_body_length: u32,
+    pub max_tags: u32,
+    pub max_tag_length: u32,
+    pub max_mentions: u32,
+    pub max_sections: u32,
+    pub max_tip_tokens: u32,
+    pub max_revisions: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_title_length: MAX_TITLE_LENGTH as u32,
+            max_body_length: MAX_BODY_LENGTH as u32,
+            max_tags: MAX_TAGS as u32,
+            max_tag_length: MAX_TAG_LENGTH as u32,
+            max_mentions: MAX_MENTIONS as u32,
+            max_sections: MAX_SECTIONS as u32,
+            max_tip_tokens: MAX_TIP_TOKEN_TYPES as u32,
+            max_revisions: MAX_REVISIONS as u32,
+        }
+    }
+}
+
+impl Limits {
+    /// Loads the limits in effect for the thread, i.e. the defaults with any
+    /// configured overrides applied.
+    pub fn load(store: &dyn Storage) -> Result<Self, ContractError> {
+        let limits = Self::default();
+        Ok(match CONFIG_LIMITS.may_load(store)? {
+            Some(overrides) => limits.with_overrides(&overrides),
+            None => limits,
+        })
+    }
+
+    /// Applies overrides on top of these limits. Overrides can only tighten a
+    /// limit, never relax it.
+    pub fn with_overrides(
+        &self,
+        overrides: &LimitOverrides,
+    ) -> Self {
+        let apply = |limit: u32, value: Option<u32>| value.map_or(limit, |v| v.min(limit));
+        Self {
+            max_title_length: apply(self.max_title_length, overrides.max_title_length),
+            max_body_length: apply(self.max_body_length, overrides.max_body_length),
+            max_tags: apply(self.max_tags, overrides.max_tags),
+            max_tag_length: apply(self.max_tag_length, overrides.max_tag_length),
+            max_mentions: apply(self.max_mentions, overrides.max_mentions),
+            max_sections: apply(self.max_sections, overrides.max_sections),
+            max_tip_tokens: self.max_tip_tokens,
+            max_revisions: apply(self.max_revisions, overrides.max_revisions),
+        }
+    }
+}
+
+/// Per-thread content limits. Unset fields fall back to the defaults in
+/// Limits, which also serve as ceilings for the values set here.
+#[cw_serde]
+pub struct LimitOverrides {
+    pub max_title_length: Option<u32>,
+    pub max_body_length: Option<u32>,
+    pub max_tags: Option<u32>,
+    pub max_tag_length: Option<u32>,
+    pub max_mentions: Option<u32>,
+    pub max_sections: Option<u32>,
+    pub max_revisions: Option<u32>,
+}
+
+/// Restricts when authors may edit their posts. Moderators, when allowed to
+/// edit at all, are not subject to these restrictions.
+#[cw_serde]
+pub struct EditPolicy {
+    /// Seconds after creation during which a post may be edited.
+    pub window_seconds: Option<u64>,
+    /// Disallow edits once a post has replies.
+    pub no_edits_after_reply: bool,
+    /// Disallow edits once a post has received any vote.
+    pub no_edits_after_vote: bool,
+    /// Allow the owner and moderators to edit any post.
+    pub moderators_can_edit: bool,
+}
+
+impl EditPolicy {
+    /// Returns the reason an author may not edit the node, if any.
+    pub fn check_author_edit(
+        &self,
+        metadata: &NodeMetadata,
+        time: Timestamp,
+    ) -> Option<String> {
+        if let Some(window_seconds) = self.window_seconds {
+            if time > metadata.created_at.plus_seconds(window_seconds) {
+                return Some(format!(
+                    "Edit window of {} seconds has passed",
+                    window_seconds
+                ));
+            }
+        }
+        if self.no_edits_after_reply && metadata.n_replies > 0 {
+            return Some("Posts with replies cannot be edited".to_owned());
+        }
+        if self.no_edits_after_vote && metadata.n_upvotes + metadata.n_downvotes > 0 {
+            return Some("Posts that have been voted on cannot be edited".to_owned());
+        }
+        None
+    }
+}
+
+/// Content of a node as it was before an edit, along with who made the edit
+/// and when. Revisions are numbered from 1 in the order the edits were made.
+#[cw_serde]
+pub struct Revision {
+    pub revision: u16,
+    pub title: Option<String>,
+    pub body: String,
+    pub sections: Vec<Section>,
+    pub tags: Vec<String>,
+    pub mentions: Vec<String>,
+    pub edited_by: Addr,
+    pub edited_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct LockMetadata {
+    pub locked_at: Timestamp,
+    pub locked_by: Addr,
+}
+
+#[cw_serde]
+pub struct PendingOwner {
+    pub owner: Owner,
+    pub proposed_by: Addr,
+    pub proposed_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct BanMetadata {
+    pub address: Addr,
+    pub banned_at: Timestamp,
+    pub banned_by: Addr,
+    pub expires_at: Option<Timestamp>,
+    pub reason: Option<String>,
+}
+
+impl BanMetadata {
+    pub fn is_active(
+        &self,
+        time: Timestamp,
+    ) -> bool {
+        match self.expires_at {
+            Some(expires_at) => time < expires_at,
+            None => true,
+        }
+    }
+}
+
+/// Progress of a state migration that hasn't completed yet.
+#[cw_serde]
+pub struct MigrationState {
+    pub from_version: String,
+    pub to_version: String,
+    /// Version of the migration currently being applied.
+    pub version: String,
+    /// Index of the next step of that migration to run.
+    pub step: u8,
+    /// Where to resume the current step from.
+    pub cursor: Option<Binary>,
 }
 
 #[cw_serde]
@@ -0,0 +1,163 @@
+//! Role-based authorization for privileged thread operations.
+//!
+//! Every privileged action is mapped to the roles allowed to perform it. A
+//! principal may perform an action if it holds any of these roles:
+//!
+//! | Action            | Owner | Moderator | Author |
+//! |-------------------|-------|-----------|--------|
+//! | SetConfig         |   x   |           |        |
+//! | Edit              |       |           |   x    |
+//! | Delete            |   x   |     x     |   x    |
+//! | HardDelete        |   x   |     x     |        |
+//! | Restore           |   x   |     x     |        |
+//! | Lock              |   x   |     x     |        |
+//! | Moderate          |   x   |     x     |        |
+//! | Ban               |   x   |     x     |        |
+//! | ManageModerators  |   x   |           |        |
+//! | TransferOwnership |   x   |           |        |
+//!
+//! The owner and moderators may also edit nodes when the thread's edit policy
+//! sets moderators_can_edit. Authors' edits are further subject to the policy.
+//!
+//! When the thread is owned by an ACL contract, the owner role is held by any
+//! principal the ACL allows to perform the action's path, e.g. "/thread/lock".
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Deps};
+use cw_acl::client::Acl;
+use cw_lib::models::Owner;
+
+use crate::error::ContractError;
+
+use super::storage::{MODERATORS, OWNER};
+
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum Role {
+    Owner,
+    Moderator,
+    Author,
+}
+
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum Action {
+    SetConfig,
+    Edit,
+    Delete,
+    HardDelete,
+    Restore,
+    Lock,
+    Moderate,
+    Ban,
+    ManageModerators,
+    TransferOwnership,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::SetConfig,
+        Action::Edit,
+        Action::Delete,
+        Action::HardDelete,
+        Action::Restore,
+        Action::Lock,
+        Action::Moderate,
+        Action::Ban,
+        Action::ManageModerators,
+        Action::TransferOwnership,
+    ];
+
+    /// ACL action path consulted when the thread is owned by an ACL contract.
+    pub fn path(&self) -> &'static str {
+        match self {
+            Action::SetConfig => "/thread/set-config",
+            Action::Edit => "/thread/edit",
+            Action::Delete => "/thread/delete",
+            Action::HardDelete => "/thread/hard-delete",
+            Action::Restore => "/thread/restore",
+            Action::Lock => "/thread/lock",
+            Action::Moderate => "/thread/moderate",
+            Action::Ban => "/thread/ban",
+            Action::ManageModerators => "/thread/moderators",
+            Action::TransferOwnership => "/thread/transfer-ownership",
+        }
+    }
+
+    /// Roles allowed to perform the action.
+    pub fn roles(&self) -> &'static [Role] {
+        match self {
+            Action::SetConfig => &[Role::Owner],
+            Action::Edit => &[Role::Author],
+            Action::Delete => &[Role::Owner, Role::Moderator, Role::Author],
+            Action::HardDelete => &[Role::Owner, Role::Moderator],
+            Action::Restore => &[Role::Owner, Role::Moderator],
+            Action::Lock => &[Role::Owner, Role::Moderator],
+            Action::Moderate => &[Role::Owner, Role::Moderator],
+            Action::Ban => &[Role::Owner, Role::Moderator],
+            Action::ManageModerators => &[Role::Owner],
+            Action::TransferOwnership => &[Role::Owner],
+        }
+    }
+}
+
+/// Returns true if the principal holds the given role with respect to the
+/// action. The author role is only held with respect to a specific node, so
+/// `author` should be the creator of the node being acted upon, if any.
+pub fn has_role(
+    deps: Deps,
+    principal: &Addr,
+    role: Role,
+    action: Action,
+    author: Option<&Addr>,
+) -> Result<bool, ContractError> {
+    Ok(match role {
+        Role::Owner => match OWNER.load(deps.storage)? {
+            Owner::Address(addr) => *principal == addr,
+            Owner::Acl(acl_addr) => {
+                let acl = Acl::new(&acl_addr);
+                acl.is_allowed(&deps.querier, principal, action.path())?
+            },
+        },
+        Role::Moderator => MODERATORS.has(deps.storage, principal),
+        Role::Author => author == Some(principal),
+    })
+}
+
+/// Returns the first role held by the principal that permits the action.
+pub fn find_authorizing_role(
+    deps: Deps,
+    principal: &Addr,
+    action: Action,
+    author: Option<&Addr>,
+) -> Result<Option<Role>, ContractError> {
+    for role in action.roles() {
+        if has_role(deps, principal, *role, action, author)? {
+            return Ok(Some(*role));
+        }
+    }
+    Ok(None)
+}
+
+pub fn is_authorized(
+    deps: Deps,
+    principal: &Addr,
+    action: Action,
+    author: Option<&Addr>,
+) -> Result<bool, ContractError> {
+    Ok(find_authorizing_role(deps, principal, action, author)?.is_some())
+}
+
+pub fn authorize(
+    deps: Deps,
+    principal: &Addr,
+    action: Action,
+    author: Option<&Addr>,
+) -> Result<Role, ContractError> {
+    match find_authorizing_role(deps, principal, action, author)? {
+        Some(role) => Ok(role),
+        None => Err(ContractError::NotAuthorized {
+            reason: format!("Not authorized to perform {}", action.path()),
+        }),
+    }
+}
@@ -1,22 +1,44 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Timestamp, Uint128};
 use cw_lib::models::{Owner, TokenV2};
 use cw_storage_plus::{Item, Map};
 
-use super::models::{FlagMetadata, NodeMetadata, Section, TableMetadata};
+use super::models::{
+    AuthorPurge, BanMetadata, EditPolicy, FlagMetadata, FlagThreshold, LimitOverrides,
+    LockMetadata, MigrationState, NodeMetadata, PendingOwner, RecycledNode, Revision, Section,
+    TableMetadata, ThreadStatus, Tombstone,
+};
 
 pub const MAX_TIP_TOKEN_TYPES: usize = 10;
+pub const DEFAULT_OWNERSHIP_TRANSFER_TTL_SECONDS: u64 = 60 * 60 * 24 * 7;
+pub const DEFAULT_RECYCLE_BIN_RETENTION_SECONDS: u64 = 60 * 60 * 24 * 30;
 
-// Validation limits
+// Validation limits. These are hard ceilings; threads may configure lower
+// limits through LimitOverrides.
 pub const MAX_TITLE_LENGTH: usize = 200;
 pub const MAX_BODY_LENGTH: usize = 50_000;
 pub const MAX_TAGS: usize = 10;
 pub const MAX_TAG_LENGTH: usize = 30;
 pub const MAX_MENTIONS: usize = 20;
 pub const MAX_SECTIONS: usize = 20;
+pub const MAX_REVISIONS: usize = 20;
 
 pub const OWNER: Item<Owner> = Item::new("owner");
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pending_owner");
+pub const MODERATORS: Map<&Addr, bool> = Map::new("moderators");
 pub const CONFIG_TIP_TOKEN_ALLOWLIST: Item<Vec<TokenV2>> = Item::new("config_tip_token_allowlist");
+pub const CONFIG_FLAG_THRESHOLD: Item<FlagThreshold> = Item::new("config_flag_threshold");
+pub const CONFIG_LIMITS: Item<LimitOverrides> = Item::new("config_limits");
+pub const CONFIG_EDIT_POLICY: Item<EditPolicy> = Item::new("config_edit_policy");
+pub const CONFIG_RECYCLE_BIN_RETENTION: Item<u64> = Item::new("config_recycle_bin_retention");
+pub const CONFIG_PRIVATE_VOTES: Item<bool> = Item::new("config_private_votes");
 pub const TABLE: Item<TableMetadata> = Item::new("table");
+pub const STATUS: Item<ThreadStatus> = Item::new("status");
+pub const MIGRATION: Item<MigrationState> = Item::new("migration");
+pub const PURGE_PENDING: Item<bool> = Item::new("purge_pending");
+pub const PENDING_DELETIONS: Map<u32, Tombstone> = Map::new("pending_deletions");
+pub const PENDING_PURGES: Map<&Addr, AuthorPurge> = Map::new("pending_purges");
+pub const RECYCLE_BIN: Map<u32, RecycledNode> = Map::new("recycle_bin");
+pub const BANS: Map<&Addr, BanMetadata> = Map::new("bans");
 pub const ACTIVITY_SCORE: Item<u32> = Item::new("activity_score");
 pub const TIP_TOKEN_LUTAB: Map<&String, bool> = Map::new("tip_token_lutab");
 
@@ -31,12 +53,25 @@ pub const NODE_ID_2_MENTIONS: Map<u32, Vec<String>> = Map::new("node_id_2_mentio
 pub const NODE_ID_2_BODY: Map<u32, String> = Map::new("node_id_2_body");
 pub const NODE_ID_2_SECTION: Map<(u32, u8), Section> = Map::new("node_id_2_section");
 pub const NODE_ID_2_FLAG: Map<(u32, &Addr), FlagMetadata> = Map::new("node_id_2_flag");
+pub const NODE_ID_2_LOCK: Map<u32, LockMetadata> = Map::new("node_id_2_lock");
+pub const NODE_ID_2_REVISION: Map<(u32, u16), Revision> = Map::new("node_id_2_revision");
+pub const NODE_ID_2_TOMBSTONE: Map<u32, Tombstone> = Map::new("node_id_2_tombstone");
 pub const NODE_ID_ADDR_2_SENTIMENT: Map<(u32, &Addr), u8> = Map::new("node_id_addr_2_sentiment");
+pub const ADDR_NODE_ID_2_SENTIMENT: Map<(&Addr, u32), u8> = Map::new("addr_node_id_2_sentiment");
+pub const NODE_ID_ADDR_2_SAVED_AT: Map<(u32, &Addr), Timestamp> =
+    Map::new("node_id_addr_2_saved_at");
 
 pub const IX_CHILD: Map<(u32, u32), bool> = Map::new("ix_child");
 pub const IX_RANKED_CHILD: Map<(u32, i32, u32), bool> = Map::new("ix_ranked_child");
+pub const IX_HOT_CHILD: Map<(u32, i64, u32), bool> = Map::new("ix_hot_child");
+pub const IX_BEST_CHILD: Map<(u32, u32, u32), bool> = Map::new("ix_best_child");
+pub const IX_CONTROVERSIAL_CHILD: Map<(u32, u32, u32), bool> = Map::new("ix_controversial_child");
 pub const IX_MENTION_NODE: Map<(&String, u32), bool> = Map::new("ix_mention");
 pub const IX_NODE_MENTION: Map<(u32, &String), bool> = Map::new("ix_node_mention");
 pub const IX_TAG_NODE: Map<(&String, u32), bool> = Map::new("ix_tag");
 pub const IX_NODE_TAG: Map<(u32, &String), bool> = Map::new("ix_node_tag");
-pub const IX_ADDR_SAVED_ID: Map<(&Addr, u32), bool> = Map::new("ix_addr_saved_id");
+pub const IX_ADDR_SAVED_AT: Map<(&Addr, u64, u32), bool> = Map::new("ix_addr_saved_at");
+pub const IX_FLAGGED: Map<(u8, u32), bool> = Map::new("ix_flagged");
+pub const IX_AUTHOR_NODE: Map<(&Addr, u32), bool> = Map::new("ix_author_node");
+pub const IX_RECYCLE_BIN_EXPIRY: Map<(u64, u32), bool> = Map::new("ix_recycle_bin_expiry");
+pub const IX_RECYCLE_BIN_DELETED_AT: Map<(u64, u32), bool> = Map::new("ix_recycle_bin_deleted_at");
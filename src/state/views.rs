@@ -1,18 +1,20 @@
-use std::marker::PhantomData;
-
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Order, Storage};
+use cosmwasm_std::{Addr, Order, Storage, Timestamp};
 use cw_lib::models::TokenV2;
-use cw_storage_plus::Bound;
 
-use crate::{error::ContractError, msg::Sentiment};
+use crate::{error::ContractError, msg::Sentiment, util::find_locked_ancestor};
 
 use super::{
-    models::{NodeMetadata, Section},
+    models::{
+        EditPolicy, FlagMetadata, FlagThreshold, LimitOverrides, LockMetadata, NodeMetadata,
+        Section, Tombstone,
+    },
     storage::{
-        CONFIG_TIP_TOKEN_ALLOWLIST, IX_ADDR_SAVED_ID, IX_NODE_MENTION, IX_NODE_TAG, NODE_ID_2_BODY,
-        NODE_ID_2_MENTIONS, NODE_ID_2_METADATA, NODE_ID_2_SECTION, NODE_ID_2_TAGS, NODE_ID_2_TITLE,
-        NODE_ID_ADDR_2_SENTIMENT,
+        CONFIG_EDIT_POLICY, CONFIG_FLAG_THRESHOLD, CONFIG_LIMITS, CONFIG_PRIVATE_VOTES,
+        CONFIG_RECYCLE_BIN_RETENTION, CONFIG_TIP_TOKEN_ALLOWLIST, IX_NODE_MENTION, IX_NODE_TAG,
+        NODE_ID_2_BODY, NODE_ID_2_FLAG, NODE_ID_2_LOCK, NODE_ID_2_MENTIONS, NODE_ID_2_METADATA,
+        NODE_ID_2_SECTION, NODE_ID_2_TAGS, NODE_ID_2_TITLE, NODE_ID_2_TOMBSTONE,
+        NODE_ID_ADDR_2_SAVED_AT, NODE_ID_ADDR_2_SENTIMENT,
     },
 };
 
@@ -31,6 +33,12 @@ pub struct NodeView {
     pub account: Option<NodeAccountView>,
     pub tags: Vec<String>,
     pub mentions: Vec<String>,
+    pub lock: Option<LockMetadata>,
+    /// ID of the nearest locked ancestor if the node isn't locked itself, in
+    /// which case it can't be replied to or voted on either.
+    pub locked_by_ancestor: Option<u32>,
+    /// Set if the node was soft deleted, in which case its content is empty.
+    pub deleted: Option<Tombstone>,
 }
 
 impl NodeView {
@@ -44,23 +52,22 @@ impl NodeView {
         let mentions = NODE_ID_2_MENTIONS.load(store, id)?;
         let tags = NODE_ID_2_TAGS.load(store, id)?;
         let title = NODE_ID_2_TITLE.may_load(store, id)?;
+        let lock = NODE_ID_2_LOCK.may_load(store, id)?;
+        let locked_by_ancestor = match (&lock, metadata.parent_id) {
+            (None, Some(parent_id)) => find_locked_ancestor(store, parent_id)?,
+            _ => None,
+        };
+        let deleted = NODE_ID_2_TOMBSTONE.may_load(store, id)?;
         let sections = NODE_ID_2_SECTION
-            .range(
-                store,
-                Some(Bound::Inclusive(((id, u8::MIN), PhantomData))),
-                None,
-                Order::Ascending,
-            )
-            .map(|r| {
-                let (_k, v) = r.unwrap();
-                v
-            })
-            .collect::<Vec<Section>>();
+            .prefix(id)
+            .range(store, None, None, Order::Ascending)
+            .map(|r| r.map(|(_, section)| section))
+            .collect::<Result<Vec<Section>, _>>()?;
 
         let account = match account_addr {
             None => None,
             Some(addr) => Some(NodeAccountView {
-                saved: IX_ADDR_SAVED_ID.has(store, (addr, id)),
+                saved: NODE_ID_ADDR_2_SAVED_AT.has(store, (id, addr)),
                 sentiment: if let Some(sent_u8) =
                     NODE_ID_ADDR_2_SENTIMENT.may_load(store, (id, addr))?
                 {
@@ -79,19 +86,64 @@ impl NodeView {
             tags,
             sections,
             account,
+            lock,
+            locked_by_ancestor,
+            deleted,
         })
     }
+
+    /// Strips the node's content, leaving only its metadata and indices.
+    pub fn redact(&mut self) {
+        self.title = None;
+        self.body = String::new();
+        self.sections = vec![];
+    }
+}
+
+#[cw_serde]
+pub struct FlaggedNodeView {
+    pub node: NodeView,
+    pub flags: Vec<FlagMetadata>,
+}
+
+impl FlaggedNodeView {
+    pub fn load_flags(
+        store: &dyn Storage,
+        id: u32,
+    ) -> Result<Vec<FlagMetadata>, ContractError> {
+        Ok(NODE_ID_2_FLAG
+            .prefix(id)
+            .range(store, None, None, Order::Ascending)
+            .map(|r| r.map(|(_, flag)| flag))
+            .collect::<Result<Vec<FlagMetadata>, _>>()?)
+    }
+}
+
+#[cw_serde]
+pub struct SavedNodeView {
+    pub node: NodeView,
+    pub saved_at: Timestamp,
 }
 
 #[cw_serde]
 pub struct ConfigView {
     pub tip_tokens: Vec<TokenV2>,
+    pub flag_threshold: Option<FlagThreshold>,
+    pub limits: Option<LimitOverrides>,
+    pub edit_policy: Option<EditPolicy>,
+    pub recycle_bin_retention_seconds: Option<u64>,
+    pub private_votes: Option<bool>,
 }
 
 impl ConfigView {
     pub fn load(store: &dyn Storage) -> Result<Self, ContractError> {
         Ok(Self {
             tip_tokens: CONFIG_TIP_TOKEN_ALLOWLIST.load(store)?,
+            flag_threshold: CONFIG_FLAG_THRESHOLD.may_load(store)?,
+            limits: CONFIG_LIMITS.may_load(store)?,
+            edit_policy: CONFIG_EDIT_POLICY.may_load(store)?,
+            recycle_bin_retention_seconds: CONFIG_RECYCLE_BIN_RETENTION.may_load(store)?,
+            private_votes: CONFIG_PRIVATE_VOTES.may_load(store)?,
         })
     }
 }
@@ -4,18 +4,24 @@
 //! to ensure the contract behaves correctly in all scenarios.
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, coins, from_json, Addr, Coin, Uint128};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{attr, coins, from_json, Addr, Env, OwnedDeps, Response, Uint128};
     use cw_lib::models::{Owner, TokenAmountV2, TokenV2};
+    use cw_table::lifecycle::{LifecycleArgs, LifecycleExecuteMsg, LifecycleSetupArgs};
+    use serde::de::DeserializeOwned;
 
     use crate::contract::{execute, instantiate, query};
     use crate::error::ContractError;
     use crate::msg::{
-        ConfigUpdate, ExecuteMsg, InstantiateMsg, NodeEditMsg, NodeReplyMsg, NodeVoteMsg,
+        ConfigUpdateMsg, ExecuteMsg, InstantiateMsg, NodeEditMsg, NodeReplyMsg, NodeVoteMsg,
         NodesQueryMsg, QueryMsg, ThreadInfoResponse,
     };
-    use crate::state::models::{Config, Section, ROOT_ID};
+    use crate::state::models::{Section, ThreadStatus, ROOT_ID};
+    use crate::state::views::{ConfigView, NodeView};
 
     // ============================================================================
     // Test Helpers
@@ -29,20 +35,20 @@ mod tests {
             sections: None,
             tags: Some(vec!["test".to_string(), "discussion".to_string()]),
             mentions: Some(vec!["@alice".to_string()]),
-            config: Config {
+            config: ConfigView {
                 tip_tokens: vec![TokenV2::Denom("uatom".to_string())],
+                flag_threshold: None,
+                limits: None,
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
             },
         }
     }
 
-    fn create_thread() -> (
-        cosmwasm_std::OwnedDeps<
-            cosmwasm_std::MemoryStorage,
-            cosmwasm_std::testing::MockApi,
-            cosmwasm_std::testing::MockQuerier,
-        >,
-        cosmwasm_std::Env,
-    ) {
+    type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn create_thread() -> (MockDeps, Env) {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info("creator", &[]);
@@ -52,6 +58,116 @@ mod tests {
         (deps, env)
     }
 
+    fn execute_as(
+        deps: &mut MockDeps,
+        env: &Env,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg)
+    }
+
+    fn query_nodes<T: DeserializeOwned>(
+        deps: &MockDeps,
+        msg: NodesQueryMsg,
+    ) -> Result<T, ContractError> {
+        query(deps.as_ref(), mock_env(), QueryMsg::Nodes(msg)).map(|res| from_json(&res).unwrap())
+    }
+
+    fn reply_to(
+        deps: &mut MockDeps,
+        env: &Env,
+        sender: &str,
+        parent_id: u32,
+    ) -> Result<Response, ContractError> {
+        execute_as(
+            deps,
+            env,
+            sender,
+            ExecuteMsg::Reply(NodeReplyMsg {
+                parent_id,
+                body: format!("Reply to {}", parent_id),
+                sections: None,
+                tags: None,
+                mentions: None,
+            }),
+        )
+    }
+
+    fn load_node(
+        deps: &MockDeps,
+        id: u32,
+        sender: Option<&str>,
+    ) -> NodeView {
+        let mut nodes: Vec<NodeView> = query_nodes(
+            deps,
+            NodesQueryMsg::ByIds {
+                ids: vec![id],
+                sender: sender.map(Addr::unchecked),
+            },
+        )
+        .unwrap();
+        nodes.remove(0)
+    }
+
+    fn flag(
+        deps: &mut MockDeps,
+        env: &Env,
+        sender: &str,
+        id: u32,
+    ) {
+        execute_as(
+            deps,
+            env,
+            sender,
+            ExecuteMsg::Flag {
+                id,
+                reason: Some("Spam".to_string()),
+            },
+        )
+        .unwrap();
+    }
+
+    fn add_moderator(
+        deps: &mut MockDeps,
+        env: &Env,
+        moderator: &str,
+    ) {
+        execute_as(
+            deps,
+            env,
+            "owner",
+            ExecuteMsg::UpdateModerators {
+                add: Some(vec![Addr::unchecked(moderator)]),
+                remove: None,
+            },
+        )
+        .unwrap();
+    }
+
+    fn edit_body(
+        deps: &mut MockDeps,
+        env: &Env,
+        sender: &str,
+        id: u32,
+        body: &str,
+    ) -> Result<Response, ContractError> {
+        execute_as(
+            deps,
+            env,
+            sender,
+            ExecuteMsg::Edit(NodeEditMsg {
+                id,
+                body: Some(body.to_string()),
+                title: None,
+                sections: None,
+                tags: None,
+                mentions: None,
+                clear: None,
+            }),
+        )
+    }
+
     // ============================================================================
     // Authorization Tests (8 tests)
     // ============================================================================
@@ -103,6 +219,7 @@ mod tests {
             sections: None,
             tags: None,
             mentions: None,
+            clear: None,
         };
         let err = execute(
             deps.as_mut(),
@@ -114,13 +231,7 @@ mod tests {
         assert!(matches!(err, ContractError::NotAuthorized { .. }));
 
         // Edit as creator - should succeed
-        execute(
-            deps.as_mut(),
-            env,
-            info_creator,
-            ExecuteMsg::Edit(edit_msg),
-        )
-        .unwrap();
+        execute(deps.as_mut(), env, info_creator, ExecuteMsg::Edit(edit_msg)).unwrap();
     }
 
     #[test]
@@ -151,13 +262,19 @@ mod tests {
             deps.as_mut(),
             env.clone(),
             info_other,
-            ExecuteMsg::Delete { id: 1 },
+            ExecuteMsg::Delete { id: 1, hard: None },
         )
         .unwrap_err();
         assert!(matches!(err, ContractError::NotAuthorized { .. }));
 
         // Delete as owner - should succeed
-        execute(deps.as_mut(), env, info_owner, ExecuteMsg::Delete { id: 1 }).unwrap();
+        execute(
+            deps.as_mut(),
+            env,
+            info_owner,
+            ExecuteMsg::Delete { id: 1, hard: None },
+        )
+        .unwrap();
     }
 
     #[test]
@@ -166,8 +283,13 @@ mod tests {
         let info_owner = mock_info("owner", &[]);
         let info_other = mock_info("other_user", &[]);
 
-        let config_update = ConfigUpdate {
+        let config_update = ConfigUpdateMsg {
             tip_tokens: Some(vec![TokenV2::Denom("uosmo".to_string())]),
+            flag_threshold: None,
+            limits: None,
+            edit_policy: None,
+            recycle_bin_retention_seconds: None,
+            private_votes: None,
         };
 
         // Try as non-owner - should fail
@@ -213,7 +335,13 @@ mod tests {
         .unwrap();
 
         // Owner deletes user's reply - should succeed
-        execute(deps.as_mut(), env, info_owner, ExecuteMsg::Delete { id: 1 }).unwrap();
+        execute(
+            deps.as_mut(),
+            env,
+            info_owner,
+            ExecuteMsg::Delete { id: 1, hard: None },
+        )
+        .unwrap();
     }
 
     #[test]
@@ -254,7 +382,7 @@ mod tests {
 
         let vote_msg = NodeVoteMsg {
             id: ROOT_ID,
-            vote: crate::state::models::Sentiment::Up,
+            sentiment: crate::msg::Sentiment::Up,
         };
 
         let res = execute(deps.as_mut(), env, info_user, ExecuteMsg::Vote(vote_msg)).unwrap();
@@ -329,6 +457,7 @@ mod tests {
         // Query children
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::Children {
             id: ROOT_ID,
+            order: None,
             cursor: None,
             limit: None,
             sender: None,
@@ -352,12 +481,19 @@ mod tests {
                 tags: None,
                 mentions: None,
             };
-            execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Reply(reply_msg)).unwrap();
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info,
+                ExecuteMsg::Reply(reply_msg),
+            )
+            .unwrap();
         }
 
         // Query children
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::Children {
             id: ROOT_ID,
+            order: None,
             cursor: None,
             limit: None,
             sender: None,
@@ -413,11 +549,13 @@ mod tests {
         let (mut deps, env) = create_thread();
         let info = mock_info("user1", &[]);
 
-        // Query initial state
-        let query_msg = QueryMsg::Thread { sender: None };
-        let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
-        let thread_info: ThreadInfoResponse = from_json(&res).unwrap();
-        let initial_score = thread_info.activity_score;
+        // The activity score isn't exposed through queries
+        let activity_score = |deps: &MockDeps| {
+            crate::state::storage::ACTIVITY_SCORE
+                .load(&deps.storage)
+                .unwrap()
+        };
+        let initial_score = activity_score(&deps);
 
         // Create a reply
         let reply_msg = NodeReplyMsg {
@@ -429,10 +567,7 @@ mod tests {
         };
         execute(deps.as_mut(), env, info, ExecuteMsg::Reply(reply_msg)).unwrap();
 
-        // Query again
-        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
-        let thread_info: ThreadInfoResponse = from_json(&res).unwrap();
-        assert!(thread_info.activity_score > initial_score);
+        assert!(activity_score(&deps) > initial_score);
     }
 
     // ============================================================================
@@ -446,7 +581,7 @@ mod tests {
 
         let vote_msg = NodeVoteMsg {
             id: ROOT_ID,
-            vote: crate::state::models::Sentiment::Up,
+            sentiment: crate::msg::Sentiment::Up,
         };
 
         execute(deps.as_mut(), env, info, ExecuteMsg::Vote(vote_msg)).unwrap();
@@ -468,7 +603,7 @@ mod tests {
 
         let vote_msg = NodeVoteMsg {
             id: ROOT_ID,
-            vote: crate::state::models::Sentiment::Down,
+            sentiment: crate::msg::Sentiment::Down,
         };
 
         execute(deps.as_mut(), env, info, ExecuteMsg::Vote(vote_msg)).unwrap();
@@ -491,7 +626,7 @@ mod tests {
         // First upvote
         let vote_msg = NodeVoteMsg {
             id: ROOT_ID,
-            vote: crate::state::models::Sentiment::Up,
+            sentiment: crate::msg::Sentiment::Up,
         };
         execute(
             deps.as_mut(),
@@ -504,7 +639,7 @@ mod tests {
         // Change to downvote
         let vote_msg = NodeVoteMsg {
             id: ROOT_ID,
-            vote: crate::state::models::Sentiment::Down,
+            sentiment: crate::msg::Sentiment::Down,
         };
         execute(deps.as_mut(), env, info, ExecuteMsg::Vote(vote_msg)).unwrap();
 
@@ -526,7 +661,7 @@ mod tests {
         // Upvote
         let vote_msg = NodeVoteMsg {
             id: ROOT_ID,
-            vote: crate::state::models::Sentiment::Up,
+            sentiment: crate::msg::Sentiment::Up,
         };
         execute(
             deps.as_mut(),
@@ -536,10 +671,10 @@ mod tests {
         )
         .unwrap();
 
-        // Remove vote (nil)
+        // Remove vote by repeating it
         let vote_msg = NodeVoteMsg {
             id: ROOT_ID,
-            vote: crate::state::models::Sentiment::Nil,
+            sentiment: crate::msg::Sentiment::Up,
         };
         execute(deps.as_mut(), env, info, ExecuteMsg::Vote(vote_msg)).unwrap();
 
@@ -581,11 +716,11 @@ mod tests {
         let votes = vec![
             NodeVoteMsg {
                 id: 1,
-                vote: crate::state::models::Sentiment::Up,
+                sentiment: crate::msg::Sentiment::Up,
             },
             NodeVoteMsg {
                 id: 2,
-                vote: crate::state::models::Sentiment::Up,
+                sentiment: crate::msg::Sentiment::Up,
             },
         ];
 
@@ -622,17 +757,20 @@ mod tests {
         execute(
             deps.as_mut(),
             env.clone(),
-            info_user.clone(),
+            info_user,
             ExecuteMsg::Reply(reply_msg),
         )
         .unwrap();
 
-        // Delete it
+        // Hard delete it
         execute(
             deps.as_mut(),
             env,
-            info_user,
-            ExecuteMsg::Delete { id: 1 },
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
         )
         .unwrap();
 
@@ -682,8 +820,17 @@ mod tests {
         )
         .unwrap();
 
-        // Delete parent (should delete child too)
-        execute(deps.as_mut(), env, info, ExecuteMsg::Delete { id: 1 }).unwrap();
+        // Hard delete parent (should delete child too)
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
 
         // Both should be gone
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::ByIds {
@@ -724,8 +871,17 @@ mod tests {
         let nodes: Vec<crate::state::views::NodeView> = from_json(&res).unwrap();
         assert_eq!(nodes[0].metadata.n_replies, 1);
 
-        // Delete the reply
-        execute(deps.as_mut(), env, info, ExecuteMsg::Delete { id: 1 }).unwrap();
+        // Hard delete the reply
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
 
         // Verify parent count is 0
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
@@ -756,7 +912,13 @@ mod tests {
         .unwrap();
 
         // Try to delete as different user
-        let err = execute(deps.as_mut(), env, info_other, ExecuteMsg::Delete { id: 1 }).unwrap_err();
+        let err = execute(
+            deps.as_mut(),
+            env,
+            info_other,
+            ExecuteMsg::Delete { id: 1, hard: None },
+        )
+        .unwrap_err();
         assert!(matches!(err, ContractError::NotAuthorized { .. }));
     }
 
@@ -765,7 +927,16 @@ mod tests {
         let (mut deps, env) = create_thread();
         let info = mock_info("user1", &[]);
 
-        let err = execute(deps.as_mut(), env, info, ExecuteMsg::Delete { id: 9999 }).unwrap_err();
+        let err = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::Delete {
+                id: 9999,
+                hard: None,
+            },
+        )
+        .unwrap_err();
         assert!(matches!(err, ContractError::NodeNotFound { .. }));
     }
 
@@ -780,7 +951,9 @@ mod tests {
         // Query nodes with tag
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::WithTag {
             tag: "test".to_string(),
+            order: None,
             cursor: None,
+            limit: None,
             sender: None,
         });
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
@@ -795,7 +968,9 @@ mod tests {
         // Query nodes with mention
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::WithMention {
             mention: "alice".to_string(),
+            order: None,
             cursor: None,
+            limit: None,
             sender: None,
         });
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
@@ -821,7 +996,9 @@ mod tests {
         // Query by tag
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::WithTag {
             tag: "rust".to_string(),
+            order: None,
             cursor: None,
+            limit: None,
             sender: None,
         });
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
@@ -847,7 +1024,9 @@ mod tests {
         // Query by mention
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::WithMention {
             mention: "bob".to_string(),
+            order: None,
             cursor: None,
+            limit: None,
             sender: None,
         });
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
@@ -884,13 +1063,16 @@ mod tests {
             sections: None,
             tags: Some(vec!["new-tag".to_string()]),
             mentions: Some(vec!["@newuser".to_string()]),
+            clear: None,
         };
         execute(deps.as_mut(), env, info, ExecuteMsg::Edit(edit_msg)).unwrap();
 
         // Query with new tag should find it
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::WithTag {
             tag: "new-tag".to_string(),
+            order: None,
             cursor: None,
+            limit: None,
             sender: None,
         });
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
@@ -900,7 +1082,9 @@ mod tests {
         // Query with old tag should not find it
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::WithTag {
             tag: "old-tag".to_string(),
+            order: None,
             cursor: None,
+            limit: None,
             sender: None,
         });
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
@@ -1077,7 +1261,12 @@ mod tests {
         let (mut deps, env) = create_thread();
         let info = mock_info("user1", &[]);
 
-        let sections: Vec<Section> = (0..25).map(|_| Section::Text("text".to_string())).collect();
+        let sections: Vec<Section> = (0..25)
+            .map(|_| Section::Text {
+                title: None,
+                body: Some("text".to_string()),
+            })
+            .collect();
         let reply_msg = NodeReplyMsg {
             parent_id: ROOT_ID,
             body: "Too many sections".to_string(),
@@ -1114,7 +1303,7 @@ mod tests {
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
         let thread_info: ThreadInfoResponse = from_json(&res).unwrap();
 
-        assert_eq!(thread_info.title, Some("Test Thread".to_string()));
+        assert_eq!(thread_info.root.title, Some("Test Thread".to_string()));
         assert_eq!(thread_info.owner, Owner::Address(Addr::unchecked("owner")));
     }
 
@@ -1159,6 +1348,7 @@ mod tests {
         // Query with limit
         let query_msg = QueryMsg::Nodes(NodesQueryMsg::Children {
             id: ROOT_ID,
+            order: None,
             cursor: None,
             limit: Some(3),
             sender: None,
@@ -1231,13 +1421,7 @@ mod tests {
         .unwrap();
 
         // Unsave the root node
-        execute(
-            deps.as_mut(),
-            env,
-            info,
-            ExecuteMsg::Unsave(vec![ROOT_ID]),
-        )
-        .unwrap();
+        execute(deps.as_mut(), env, info, ExecuteMsg::Unsave(vec![ROOT_ID])).unwrap();
     }
 
     #[test]
@@ -1258,13 +1442,7 @@ mod tests {
         .unwrap();
 
         // Unflag the root node
-        execute(
-            deps.as_mut(),
-            env,
-            info,
-            ExecuteMsg::Unflag { id: ROOT_ID },
-        )
-        .unwrap();
+        execute(deps.as_mut(), env, info, ExecuteMsg::Unflag { id: ROOT_ID }).unwrap();
     }
 
     #[test]
@@ -1277,8 +1455,14 @@ mod tests {
             parent_id: ROOT_ID,
             body: "Original".to_string(),
             sections: Some(vec![
-                Section::Text("Section 1".to_string()),
-                Section::Text("Section 2".to_string()),
+                Section::Text {
+                    title: None,
+                    body: Some("Section 1".to_string()),
+                },
+                Section::Text {
+                    title: None,
+                    body: Some("Section 2".to_string()),
+                },
             ]),
             tags: None,
             mentions: None,
@@ -1296,9 +1480,13 @@ mod tests {
             id: 1,
             body: Some("Updated".to_string()),
             title: None,
-            sections: Some(vec![Section::Text("New Section".to_string())]),
+            sections: Some(vec![Section::Text {
+                title: None,
+                body: Some("New Section".to_string()),
+            }]),
             tags: None,
             mentions: None,
+            clear: None,
         };
         execute(deps.as_mut(), env, info, ExecuteMsg::Edit(edit_msg)).unwrap();
 
@@ -1349,4 +1537,3111 @@ mod tests {
 
         execute(deps.as_mut(), env, info, ExecuteMsg::Reply(reply_msg)).unwrap();
     }
+
+    // ============================================================================
+    // Thread Status Tests (2 tests)
+    // ============================================================================
+
+    fn create_thread_with_table() -> (MockDeps, Env) {
+        let (mut deps, env) = create_thread();
+        execute_as(
+            &mut deps,
+            &env,
+            "table",
+            ExecuteMsg::Lifecycle(LifecycleExecuteMsg::Setup(LifecycleSetupArgs {
+                initiator: Addr::unchecked("creator"),
+                id: "thread".to_string(),
+            })),
+        )
+        .unwrap();
+        (deps, env)
+    }
+
+    fn lifecycle_args() -> LifecycleArgs {
+        LifecycleArgs {
+            initiator: Addr::unchecked("table"),
+        }
+    }
+
+    #[test]
+    fn test_table_suspends_and_resumes_thread() {
+        let (mut deps, env) = create_thread_with_table();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+
+        // Only the table can change the thread's status
+        for msg in [
+            LifecycleExecuteMsg::Suspend(lifecycle_args()),
+            LifecycleExecuteMsg::Resume(lifecycle_args()),
+            LifecycleExecuteMsg::Teardown(lifecycle_args()),
+        ] {
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("owner", &[]),
+                ExecuteMsg::Lifecycle(msg),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::NotAuthorized { .. }));
+        }
+
+        // An active thread can't be resumed
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("table", &[]),
+            ExecuteMsg::Lifecycle(LifecycleExecuteMsg::Resume(lifecycle_args())),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("table", &[]),
+            ExecuteMsg::Lifecycle(LifecycleExecuteMsg::Suspend(lifecycle_args())),
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("table", &[]),
+            ExecuteMsg::Lifecycle(LifecycleExecuteMsg::Suspend(lifecycle_args())),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+
+        let vote = || NodeVoteMsg {
+            id: 1,
+            sentiment: crate::msg::Sentiment::Up,
+        };
+        let mutations = || {
+            vec![
+                (
+                    mock_info("user2", &[]),
+                    ExecuteMsg::Reply(NodeReplyMsg {
+                        parent_id: ROOT_ID,
+                        body: "Reply".to_string(),
+                        sections: None,
+                        tags: None,
+                        mentions: None,
+                    }),
+                ),
+                (mock_info("user2", &[]), ExecuteMsg::Vote(vote())),
+                (mock_info("user3", &[]), ExecuteMsg::VoteMany(vec![vote()])),
+                (
+                    mock_info("user1", &[]),
+                    ExecuteMsg::Edit(NodeEditMsg {
+                        id: 1,
+                        body: Some("Edited".to_string()),
+                        title: None,
+                        sections: None,
+                        tags: None,
+                        mentions: None,
+                        clear: None,
+                    }),
+                ),
+                (
+                    mock_info("tipper", &coins(100, "uatom")),
+                    ExecuteMsg::Tip(TokenAmountV2 {
+                        token: TokenV2::Denom("uatom".to_string()),
+                        amount: Uint128::new(100),
+                    }),
+                ),
+                (
+                    mock_info("user2", &[]),
+                    ExecuteMsg::Flag {
+                        id: 1,
+                        reason: None,
+                    },
+                ),
+                (mock_info("user2", &[]), ExecuteMsg::Save(vec![1])),
+            ]
+        };
+
+        for (info, msg) in mutations() {
+            let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+            assert!(matches!(err, ContractError::ThreadNotActive { .. }));
+        }
+
+        // Queries keep working and report the status
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Thread { sender: None }).unwrap();
+        let thread_info: ThreadInfoResponse = from_json(&res).unwrap();
+        assert_eq!(thread_info.status, ThreadStatus::Suspended);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("table", &[]),
+            ExecuteMsg::Lifecycle(LifecycleExecuteMsg::Resume(lifecycle_args())),
+        )
+        .unwrap();
+
+        for (info, msg) in mutations() {
+            execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Thread { sender: None }).unwrap();
+        let thread_info: ThreadInfoResponse = from_json(&res).unwrap();
+        assert_eq!(thread_info.status, ThreadStatus::Active);
+    }
+
+    #[test]
+    fn test_table_teardown_terminates_thread() {
+        let (mut deps, env) = create_thread_with_table();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("table", &[]),
+            ExecuteMsg::Lifecycle(LifecycleExecuteMsg::Teardown(lifecycle_args())),
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Thread { sender: None }).unwrap();
+        let thread_info: ThreadInfoResponse = from_json(&res).unwrap();
+        assert_eq!(thread_info.status, ThreadStatus::Terminated);
+
+        // Teardown is final
+        for msg in [
+            LifecycleExecuteMsg::Resume(lifecycle_args()),
+            LifecycleExecuteMsg::Suspend(lifecycle_args()),
+        ] {
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("table", &[]),
+                ExecuteMsg::Lifecycle(msg),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::ValidationError { .. }));
+        }
+
+        let err = reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap_err();
+        assert!(matches!(err, ContractError::ThreadNotActive { .. }));
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: ROOT_ID,
+                hard: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ThreadNotActive { .. }));
+    }
+
+    // ============================================================================
+    // Lock Tests (3 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_only_owner_can_lock() {
+        let (mut deps, env) = create_thread();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other_user", &[]),
+            ExecuteMsg::Lock { id: ROOT_ID },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Lock { id: ROOT_ID },
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nodes(NodesQueryMsg::ByIds {
+                ids: vec![ROOT_ID],
+                sender: None,
+            }),
+        )
+        .unwrap();
+        let nodes: Vec<crate::state::views::NodeView> = from_json(&res).unwrap();
+        assert_eq!(
+            nodes[0].lock.as_ref().unwrap().locked_by,
+            Addr::unchecked("owner")
+        );
+    }
+
+    #[test]
+    fn test_locked_subtree_rejects_replies_and_votes() {
+        let (mut deps, env) = create_thread();
+
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "user1", 1).unwrap(); // 2
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 3
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Lock { id: 1 },
+        )
+        .unwrap();
+
+        let err = reply_to(&mut deps, &env, "user2", 2).unwrap_err();
+        assert!(matches!(err, ContractError::NodeLocked { node_id: 1 }));
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user2", &[]),
+            ExecuteMsg::Vote(NodeVoteMsg {
+                id: 2,
+                sentiment: crate::msg::Sentiment::Up,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NodeLocked { node_id: 1 }));
+
+        // Views report the lock that applies to each node
+        let node = load_node(&deps, 1, None);
+        assert!(node.lock.is_some());
+        assert_eq!(node.locked_by_ancestor, None);
+        let node = load_node(&deps, 2, None);
+        assert!(node.lock.is_none());
+        assert_eq!(node.locked_by_ancestor, Some(1));
+        assert_eq!(load_node(&deps, 3, None).locked_by_ancestor, None);
+
+        // Sibling subtrees are unaffected
+        reply_to(&mut deps, &env, "user2", 3).unwrap();
+    }
+
+    #[test]
+    fn test_unlock_reopens_node() {
+        let (mut deps, env) = create_thread();
+        let info_owner = mock_info("owner", &[]);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info_owner.clone(),
+            ExecuteMsg::Lock { id: ROOT_ID },
+        )
+        .unwrap();
+        assert!(reply_to(&mut deps, &env, "user1", ROOT_ID).is_err());
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info_owner,
+            ExecuteMsg::Unlock { id: ROOT_ID },
+        )
+        .unwrap();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+    }
+
+    // ============================================================================
+    // Flag Threshold Tests (3 tests)
+    // ============================================================================
+
+    fn create_thread_with_flag_threshold() -> (MockDeps, Env) {
+        let (mut deps, env) = create_thread();
+        execute_as(
+            &mut deps,
+            &env,
+            "owner",
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: Some(crate::state::models::FlagThreshold {
+                    count: 2,
+                    ratio: None,
+                }),
+                limits: None,
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap();
+        (deps, env)
+    }
+
+    #[test]
+    fn test_flag_threshold_hides_and_redacts_node() {
+        let (mut deps, env) = create_thread_with_flag_threshold();
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap();
+
+        flag(&mut deps, &env, "user1", 1);
+        assert!(load_node(&deps, 1, None).metadata.hidden_at.is_none());
+
+        flag(&mut deps, &env, "user2", 1);
+
+        // Redacted for the public
+        let node = load_node(&deps, 1, Some("user3"));
+        assert!(node.metadata.hidden_at.is_some());
+        assert_eq!(node.body, "");
+
+        // Visible to the author and to moderators
+        assert_eq!(load_node(&deps, 1, Some("author")).body, "Reply to 0");
+        assert_eq!(load_node(&deps, 1, Some("owner")).body, "Reply to 0");
+    }
+
+    #[test]
+    fn test_flag_ratio_counts_upvotes_not_net_rank() {
+        let (mut deps, env) = create_thread();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: Some(crate::state::models::FlagThreshold {
+                    count: 2,
+                    ratio: Some(cosmwasm_std::Decimal::percent(50)),
+                }),
+                limits: None,
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap();
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap();
+
+        // 10 up and 9 down leaves a net rank of 1
+        for i in 0..19 {
+            let sentiment = if i < 10 {
+                crate::msg::Sentiment::Up
+            } else {
+                crate::msg::Sentiment::Down
+            };
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(&format!("voter{}", i), &[]),
+                ExecuteMsg::Vote(NodeVoteMsg { id: 1, sentiment }),
+            )
+            .unwrap();
+        }
+
+        // 2 flags per 10 upvotes is below the ratio
+        flag(&mut deps, &env, "user1", 1);
+        flag(&mut deps, &env, "user2", 1);
+        assert!(load_node(&deps, 1, None).metadata.hidden_at.is_none());
+    }
+
+    #[test]
+    fn test_moderator_can_unhide_node() {
+        let (mut deps, env) = create_thread_with_flag_threshold();
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap();
+        flag(&mut deps, &env, "user1", 1);
+        flag(&mut deps, &env, "user2", 1);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user3", &[]),
+            ExecuteMsg::Unhide { id: 1 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Unhide { id: 1 },
+        )
+        .unwrap();
+
+        let node = load_node(&deps, 1, None);
+        assert!(node.metadata.hidden_at.is_none());
+        assert_eq!(node.metadata.n_flags, 0);
+        assert_eq!(node.body, "Reply to 0");
+    }
+
+    // ============================================================================
+    // Moderation Queue Tests (3 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_query_flagged_nodes_ordered_by_flag_count() {
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap(); // 2
+
+        flag(&mut deps, &env, "user1", 1);
+        flag(&mut deps, &env, "user1", 2);
+        flag(&mut deps, &env, "user2", 2);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nodes(NodesQueryMsg::Flagged {
+                cursor: None,
+                limit: None,
+                sender: None,
+            }),
+        )
+        .unwrap();
+        let response: crate::msg::FlaggedNodesPaginationResponse = from_json(&res).unwrap();
+
+        assert_eq!(response.nodes.len(), 2);
+        assert_eq!(response.nodes[0].node.metadata.id, 2);
+        assert_eq!(response.nodes[0].flags.len(), 2);
+        assert_eq!(response.nodes[1].node.metadata.id, 1);
+        assert_eq!(response.nodes[1].flags[0].reason, Some("Spam".to_string()));
+    }
+
+    #[test]
+    fn test_query_flagged_nodes_caps_scan() {
+        use crate::query::nodes::MAX_SCANNED_IDS;
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap(); // 1
+        flag(&mut deps, &env, "user1", 1);
+
+        // Flag a large subtree, then hard delete it so that most of it is
+        // still pending deletion
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap(); // 2
+        for id in 3..3 + MAX_SCANNED_IDS as u32 + 120 {
+            reply_to(&mut deps, &env, "author", 2).unwrap();
+            flag(&mut deps, &env, "user1", id);
+        }
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 2,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("pending", "true")));
+
+        let flagged = |cursor: Option<(u8, u32)>| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nodes(NodesQueryMsg::Flagged {
+                    cursor,
+                    limit: None,
+                    sender: None,
+                }),
+            )
+            .unwrap();
+            let response: crate::msg::FlaggedNodesPaginationResponse = from_json(&res).unwrap();
+            let ids: Vec<u32> = response.nodes.iter().map(|n| n.node.metadata.id).collect();
+            (ids, response.cursor)
+        };
+
+        // The scan stops after MAX_SCANNED_IDS entries and resumes from there
+        let (ids, cursor) = flagged(None);
+        assert!(ids.is_empty());
+        assert!(cursor.is_some());
+        assert_eq!(flagged(cursor), (vec![1], None));
+    }
+
+    #[test]
+    fn test_resolve_flags() {
+        let (mut deps, env) = create_thread();
+        let info_owner = mock_info("owner", &[]);
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "author", ROOT_ID).unwrap(); // 2
+        flag(&mut deps, &env, "user1", 1);
+        flag(&mut deps, &env, "user1", 2);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user2", &[]),
+            ExecuteMsg::ResolveFlags {
+                id: 1,
+                action: crate::msg::FlagResolution::Dismiss,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info_owner.clone(),
+            ExecuteMsg::ResolveFlags {
+                id: 1,
+                action: crate::msg::FlagResolution::Dismiss,
+            },
+        )
+        .unwrap();
+        assert_eq!(load_node(&deps, 1, None).metadata.n_flags, 0);
+
+        execute(
+            deps.as_mut(),
+            env,
+            info_owner,
+            ExecuteMsg::ResolveFlags {
+                id: 2,
+                action: crate::msg::FlagResolution::Delete,
+            },
+        )
+        .unwrap();
+
+        // Both nodes have left the moderation queue
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nodes(NodesQueryMsg::Flagged {
+                cursor: None,
+                limit: None,
+                sender: None,
+            }),
+        )
+        .unwrap();
+        let response: crate::msg::FlaggedNodesPaginationResponse = from_json(&res).unwrap();
+        assert!(response.nodes.is_empty());
+    }
+
+    // ============================================================================
+    // Ban Tests (4 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_banned_address_cannot_reply_or_vote() {
+        let (mut deps, env) = create_thread();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other_user", &[]),
+            ExecuteMsg::Ban {
+                address: Addr::unchecked("troll"),
+                expires_at: None,
+                reason: None,
+                purge: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Ban {
+                address: Addr::unchecked("troll"),
+                expires_at: None,
+                reason: Some("Abuse".to_string()),
+                purge: None,
+            },
+        )
+        .unwrap();
+
+        let err = reply_to(&mut deps, &env, "troll", ROOT_ID).unwrap_err();
+        assert!(matches!(err, ContractError::Banned { .. }));
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("troll", &[]),
+            ExecuteMsg::Vote(NodeVoteMsg {
+                id: ROOT_ID,
+                sentiment: crate::msg::Sentiment::Down,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Banned { .. }));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Unban {
+                address: Addr::unchecked("troll"),
+            },
+        )
+        .unwrap();
+        reply_to(&mut deps, &env, "troll", ROOT_ID).unwrap();
+    }
+
+    #[test]
+    fn test_ban_expires() {
+        let (mut deps, mut env) = create_thread();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Ban {
+                address: Addr::unchecked("troll"),
+                expires_at: Some(env.block.time.plus_seconds(60)),
+                reason: None,
+                purge: None,
+            },
+        )
+        .unwrap();
+        assert!(reply_to(&mut deps, &env, "troll", ROOT_ID).is_err());
+
+        env.block.time = env.block.time.plus_seconds(60);
+        reply_to(&mut deps, &env, "troll", ROOT_ID).unwrap();
+    }
+
+    #[test]
+    fn test_ban_with_purge_deletes_nodes() {
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "troll", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "troll", 1).unwrap(); // 2
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 3
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Ban {
+                address: Addr::unchecked("troll"),
+                expires_at: None,
+                reason: None,
+                purge: Some(true),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("n_purged", "1"));
+
+        assert_eq!(load_node(&deps, ROOT_ID, None).metadata.n_replies, 1);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bans {
+                cursor: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let response: crate::msg::BansPaginationResponse = from_json(&res).unwrap();
+        assert_eq!(response.bans.len(), 1);
+        assert_eq!(response.bans[0].address, Addr::unchecked("troll"));
+    }
+
+    #[test]
+    fn test_ban_purge_continues_in_batches() {
+        let (mut deps, env) = create_thread();
+        for _ in 0..120 {
+            reply_to(&mut deps, &env, "troll", ROOT_ID).unwrap();
+        }
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Ban {
+                address: Addr::unchecked("troll"),
+                expires_at: None,
+                reason: None,
+                purge: Some(true),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("n_purged", "100"));
+        assert!(res.attributes.contains(&attr("pending", "true")));
+
+        // Nodes that haven't been visited yet are still in place
+        assert_eq!(load_node(&deps, ROOT_ID, None).metadata.n_replies, 21);
+
+        loop {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("anyone", &[]),
+                ExecuteMsg::ContinueDeletion { limit: Some(50) },
+            )
+            .unwrap();
+            if res.attributes.contains(&attr("pending", "false")) {
+                break;
+            }
+        }
+
+        assert_eq!(load_node(&deps, ROOT_ID, None).metadata.n_replies, 1);
+        assert!(crate::state::storage::IX_AUTHOR_NODE
+            .prefix(&Addr::unchecked("troll"))
+            .keys(&deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .next()
+            .is_none());
+    }
+
+    // ============================================================================
+    // Ownership Transfer Tests (3 tests)
+    // ============================================================================
+
+    fn propose_owner(
+        deps: &mut MockDeps,
+        env: &Env,
+        sender: &str,
+        new_owner: &str,
+    ) -> Result<Response, ContractError> {
+        execute_as(
+            deps,
+            env,
+            sender,
+            ExecuteMsg::TransferOwnership {
+                new_owner: Owner::Address(Addr::unchecked(new_owner)),
+                expires_at: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_two_step_ownership_transfer() {
+        let (mut deps, env) = create_thread();
+
+        let err = propose_owner(&mut deps, &env, "other_user", "other_user").unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        propose_owner(&mut deps, &env, "owner", "new_owner").unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Thread { sender: None }).unwrap();
+        let thread_info: ThreadInfoResponse = from_json(&res).unwrap();
+        assert_eq!(thread_info.owner, Owner::Address(Addr::unchecked("owner")));
+        assert_eq!(
+            thread_info.pending_owner.unwrap().owner,
+            Owner::Address(Addr::unchecked("new_owner"))
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other_user", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("new_owner", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Thread { sender: None }).unwrap();
+        let thread_info: ThreadInfoResponse = from_json(&res).unwrap();
+        assert_eq!(
+            thread_info.owner,
+            Owner::Address(Addr::unchecked("new_owner"))
+        );
+        assert!(thread_info.pending_owner.is_none());
+    }
+
+    #[test]
+    fn test_expired_ownership_transfer_cannot_be_accepted() {
+        let (mut deps, mut env) = create_thread();
+        propose_owner(&mut deps, &env, "owner", "new_owner").unwrap();
+
+        env.block.time = env
+            .block
+            .time
+            .plus_seconds(crate::state::storage::DEFAULT_OWNERSHIP_TRANSFER_TTL_SECONDS);
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("new_owner", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+    }
+
+    #[test]
+    fn test_cancel_ownership_transfer() {
+        let (mut deps, env) = create_thread();
+        propose_owner(&mut deps, &env, "owner", "new_owner").unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::CancelOwnershipTransfer {},
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("new_owner", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+    }
+
+    // ============================================================================
+    // Permission Tests (3 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_root_creator_cannot_set_config_unless_owner() {
+        let (mut deps, env) = create_thread();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: None,
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: None,
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_moderator_can_lock_and_delete_but_not_manage_moderators() {
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 2
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mod", &[]),
+            ExecuteMsg::Lock { id: 1 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        add_moderator(&mut deps, &env, "mod");
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mod", &[]),
+            ExecuteMsg::Lock { id: 1 },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mod", &[]),
+            ExecuteMsg::Delete { id: 2, hard: None },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mod", &[]),
+            ExecuteMsg::UpdateModerators {
+                add: Some(vec![Addr::unchecked("other_user")]),
+                remove: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::UpdateModerators {
+                add: None,
+                remove: Some(vec![Addr::unchecked("mod")]),
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("mod", &[]),
+            ExecuteMsg::Unlock { id: 1 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+    }
+
+    #[test]
+    fn test_permissions_query() {
+        use crate::msg::PermissionsResponse;
+        use crate::state::permissions::{Action, Role};
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        add_moderator(&mut deps, &env, "mod");
+
+        let query_permissions = |address: &str, id: Option<u32>| -> PermissionsResponse {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Permissions {
+                    address: Addr::unchecked(address),
+                    id,
+                },
+            )
+            .unwrap();
+            from_json(&res).unwrap()
+        };
+
+        let owner = query_permissions("owner", None);
+        assert_eq!(owner.roles, vec![Role::Owner]);
+        assert!(owner.actions.contains(&Action::SetConfig));
+        assert!(!owner.actions.contains(&Action::Edit));
+
+        let moderator = query_permissions("mod", None);
+        assert_eq!(moderator.roles, vec![Role::Moderator]);
+        assert!(moderator.actions.contains(&Action::Lock));
+        assert!(!moderator.actions.contains(&Action::SetConfig));
+
+        let author = query_permissions("user1", Some(1));
+        assert_eq!(author.roles, vec![Role::Author]);
+        assert_eq!(author.actions, vec![Action::Edit, Action::Delete]);
+
+        assert!(query_permissions("user1", None).actions.is_empty());
+    }
+
+    // ============================================================================
+    // Config Query Tests (1 test)
+    // ============================================================================
+
+    #[test]
+    fn test_query_config_and_version() {
+        let (deps, _env) = create_thread();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let response: crate::msg::ConfigResponse = from_json(&res).unwrap();
+        assert_eq!(
+            response.config.tip_tokens,
+            vec![TokenV2::Denom("uatom".to_string())]
+        );
+        assert_eq!(
+            response.limits.max_body_length,
+            crate::state::storage::MAX_BODY_LENGTH as u32
+        );
+        assert_eq!(
+            response.limits.max_tags,
+            crate::state::storage::MAX_TAGS as u32
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Version {}).unwrap();
+        let version: cw2::ContractVersion = from_json(&res).unwrap();
+        assert_eq!(version.contract, "crates.io:cw-thread");
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    // ============================================================================
+    // Limit Override Tests (2 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_limit_overrides_apply_to_replies() {
+        use crate::state::models::LimitOverrides;
+
+        let (mut deps, env) = create_thread();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: Some(LimitOverrides {
+                    max_title_length: None,
+                    max_body_length: Some(10),
+                    max_tags: Some(0),
+                    max_tag_length: None,
+                    max_mentions: None,
+                    max_sections: None,
+                    max_revisions: None,
+                }),
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap();
+
+        // "Reply to 0" is exactly 10 characters
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user1", &[]),
+            ExecuteMsg::Reply(NodeReplyMsg {
+                parent_id: ROOT_ID,
+                body: "This body is too long".to_string(),
+                sections: None,
+                tags: None,
+                mentions: None,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("user1", &[]),
+            ExecuteMsg::Reply(NodeReplyMsg {
+                parent_id: ROOT_ID,
+                body: "Short".to_string(),
+                sections: None,
+                tags: Some(vec!["tag".to_string()]),
+                mentions: None,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let response: crate::msg::ConfigResponse = from_json(&res).unwrap();
+        assert_eq!(response.limits.max_body_length, 10);
+        assert_eq!(response.limits.max_tags, 0);
+        assert_eq!(
+            response.limits.max_sections,
+            crate::state::storage::MAX_SECTIONS as u32
+        );
+    }
+
+    #[test]
+    fn test_limit_overrides_cannot_exceed_ceilings() {
+        use crate::state::models::LimitOverrides;
+
+        let (mut deps, env) = create_thread();
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: Some(LimitOverrides {
+                    max_title_length: None,
+                    max_body_length: Some(crate::state::storage::MAX_BODY_LENGTH as u32 + 1),
+                    max_tags: None,
+                    max_tag_length: None,
+                    max_mentions: None,
+                    max_sections: None,
+                    max_revisions: None,
+                }),
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+    }
+
+    // ============================================================================
+    // Migration Tests (3 tests)
+    // ============================================================================
+
+    fn migrate_msg(batch_size: Option<u32>) -> crate::msg::MigrateMsg {
+        crate::msg::MigrateMsg {
+            config: None,
+            batch_size,
+        }
+    }
+
+    #[test]
+    fn test_batched_migration_from_0_0_1() {
+        use crate::state::storage::{IX_AUTHOR_NODE, STATUS};
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "troll", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "troll", ROOT_ID).unwrap(); // 2
+
+        // Roll state back to what 0.0.1 would have written
+        cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw-thread", "0.0.1").unwrap();
+        STATUS.remove(deps.as_mut().storage);
+        IX_AUTHOR_NODE.clear(deps.as_mut().storage);
+
+        let res =
+            crate::contract::migrate(deps.as_mut(), env.clone(), migrate_msg(Some(2))).unwrap();
+        assert!(res.attributes.contains(&attr("pending", "true")));
+
+        let err = reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap_err();
+        assert!(matches!(err, ContractError::MigrationPending {}));
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ContinueMigration { limit: Some(10) },
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("pending", "false")));
+
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+
+        // Purging relies on the backfilled author index
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Ban {
+                address: Addr::unchecked("troll"),
+                expires_at: None,
+                reason: None,
+                purge: Some(true),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("n_purged", "2"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Version {}).unwrap();
+        let version: cw2::ContractVersion = from_json(&res).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_migration_refuses_downgrade_and_foreign_contract() {
+        let (mut deps, env) = create_thread();
+
+        cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw-thread", "99.0.0").unwrap();
+        let err =
+            crate::contract::migrate(deps.as_mut(), env.clone(), migrate_msg(None)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMigration { .. }));
+
+        cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw-other", "0.0.1").unwrap();
+        let err = crate::contract::migrate(deps.as_mut(), env, migrate_msg(None)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMigration { .. }));
+    }
+
+    #[test]
+    fn test_migration_applies_config() {
+        let (mut deps, env) = create_thread();
+
+        crate::contract::migrate(
+            deps.as_mut(),
+            env,
+            crate::msg::MigrateMsg {
+                config: Some(ConfigUpdateMsg {
+                    tip_tokens: Some(vec![TokenV2::Denom("uosmo".to_string())]),
+                    flag_threshold: None,
+                    limits: None,
+                    edit_policy: None,
+                    recycle_bin_retention_seconds: None,
+                    private_votes: None,
+                }),
+                batch_size: None,
+            },
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let response: crate::msg::ConfigResponse = from_json(&res).unwrap();
+        assert_eq!(
+            response.config.tip_tokens,
+            vec![TokenV2::Denom("uosmo".to_string())]
+        );
+    }
+
+    // ============================================================================
+    // Revision Tests (2 tests)
+    // ============================================================================
+
+    fn query_revisions(
+        deps: &MockDeps,
+        id: u32,
+    ) -> crate::msg::RevisionsPaginationResponse {
+        query_nodes(
+            deps,
+            NodesQueryMsg::Revisions {
+                id,
+                cursor: None,
+                limit: None,
+                sender: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_edit_appends_revision() {
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+
+        edit_body(&mut deps, &env, "user1", 1, "First edit").unwrap();
+
+        let response = query_revisions(&deps, 1);
+        assert_eq!(response.revisions.len(), 1);
+        assert_eq!(response.revisions[0].revision, 1);
+        assert_eq!(response.revisions[0].body, "Reply to 0");
+        assert_eq!(response.revisions[0].edited_by, Addr::unchecked("user1"));
+        assert_eq!(load_node(&deps, 1, None).metadata.n_revisions, 1);
+        assert_eq!(load_node(&deps, 1, None).body, "First edit");
+    }
+
+    #[test]
+    fn test_revisions_are_capped() {
+        use crate::state::models::LimitOverrides;
+
+        let (mut deps, env) = create_thread();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: Some(LimitOverrides {
+                    max_title_length: None,
+                    max_body_length: None,
+                    max_tags: None,
+                    max_tag_length: None,
+                    max_mentions: None,
+                    max_sections: None,
+                    max_revisions: Some(2),
+                }),
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+
+        edit_body(&mut deps, &env, "user1", 1, "Edit 1").unwrap();
+        edit_body(&mut deps, &env, "user1", 1, "Edit 2").unwrap();
+        edit_body(&mut deps, &env, "user1", 1, "Edit 3").unwrap();
+
+        let response = query_revisions(&deps, 1);
+        let bodies: Vec<&str> = response.revisions.iter().map(|r| r.body.as_str()).collect();
+        assert_eq!(bodies, vec!["Edit 2", "Edit 1"]);
+        assert_eq!(response.revisions[0].revision, 3);
+        assert_eq!(load_node(&deps, 1, None).metadata.n_revisions, 3);
+    }
+
+    // ============================================================================
+    // Partial Edit Tests (2 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_retag_without_resending_body() {
+        use crate::msg::NodeEditField;
+
+        let (mut deps, env) = create_thread();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Edit(NodeEditMsg {
+                id: ROOT_ID,
+                body: None,
+                title: Some("Retitled".to_string()),
+                sections: None,
+                tags: Some(vec!["retagged".to_string()]),
+                mentions: None,
+                clear: None,
+            }),
+        )
+        .unwrap();
+
+        let root = load_node(&deps, ROOT_ID, None);
+        assert_eq!(root.body, "This is a test thread body");
+        assert_eq!(root.title, Some("Retitled".to_string()));
+        assert_eq!(root.tags, vec!["retagged".to_string()]);
+        assert_eq!(root.mentions, vec!["@alice".to_string()]);
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("creator", &[]),
+            ExecuteMsg::Edit(NodeEditMsg {
+                id: ROOT_ID,
+                body: None,
+                title: None,
+                sections: None,
+                tags: None,
+                mentions: None,
+                clear: Some(vec![NodeEditField::Tags, NodeEditField::Mentions]),
+            }),
+        )
+        .unwrap();
+
+        let root = load_node(&deps, ROOT_ID, None);
+        assert!(root.tags.is_empty());
+        assert!(root.mentions.is_empty());
+        assert_eq!(root.title, Some("Retitled".to_string()));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nodes(NodesQueryMsg::WithTag {
+                tag: "retagged".to_string(),
+                order: None,
+                cursor: None,
+                limit: None,
+                sender: None,
+            }),
+        )
+        .unwrap();
+        let response: crate::msg::NodeViewByTagPaginationResponse = from_json(&res).unwrap();
+        assert!(response.nodes.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_partial_edits() {
+        use crate::msg::NodeEditField;
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+
+        let edit = |id: u32, title: Option<String>, clear: Option<Vec<NodeEditField>>| {
+            ExecuteMsg::Edit(NodeEditMsg {
+                id,
+                body: None,
+                title,
+                sections: None,
+                tags: Some(vec!["tag".to_string()]),
+                mentions: None,
+                clear,
+            })
+        };
+
+        // Setting and clearing the same field
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user1", &[]),
+            edit(1, None, Some(vec![NodeEditField::Tags])),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+
+        // Only the root node has a title
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("user1", &[]),
+            edit(1, Some("Title".to_string()), None),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+    }
+
+    // ============================================================================
+    // Edit Policy Tests (3 tests)
+    // ============================================================================
+
+    fn set_edit_policy(
+        deps: &mut MockDeps,
+        env: &Env,
+        policy: crate::state::models::EditPolicy,
+    ) {
+        execute_as(
+            deps,
+            env,
+            "owner",
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: None,
+                edit_policy: Some(policy),
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_edit_policy_restricts_author_edits() {
+        let (mut deps, env) = create_thread();
+
+        set_edit_policy(
+            &mut deps,
+            &env,
+            crate::state::models::EditPolicy {
+                window_seconds: Some(60),
+                no_edits_after_reply: true,
+                no_edits_after_vote: false,
+                moderators_can_edit: false,
+            },
+        );
+
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+
+        // The root now has a reply
+        let err = edit_body(&mut deps, &env, "creator", ROOT_ID, "Edited").unwrap_err();
+        assert!(matches!(err, ContractError::EditNotAllowed { .. }));
+
+        // The reply is editable within the window but not after it
+        edit_body(&mut deps, &env, "alice", 1, "Edited").unwrap();
+
+        let mut later = env.clone();
+        later.block.time = env.block.time.plus_seconds(61);
+        let err = edit_body(&mut deps, &later, "alice", 1, "Edited again").unwrap_err();
+        assert!(matches!(err, ContractError::EditNotAllowed { .. }));
+    }
+
+    #[test]
+    fn test_no_edits_after_offsetting_votes() {
+        let (mut deps, env) = create_thread();
+
+        set_edit_policy(
+            &mut deps,
+            &env,
+            crate::state::models::EditPolicy {
+                window_seconds: None,
+                no_edits_after_reply: false,
+                no_edits_after_vote: true,
+                moderators_can_edit: false,
+            },
+        );
+
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        edit_body(&mut deps, &env, "alice", 1, "Edited").unwrap();
+
+        // One up and one down leave a net rank of zero
+        for (voter, sentiment) in [
+            ("bob", crate::msg::Sentiment::Up),
+            ("carol", crate::msg::Sentiment::Down),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(voter, &[]),
+                ExecuteMsg::Vote(NodeVoteMsg { id: 1, sentiment }),
+            )
+            .unwrap();
+        }
+        assert_eq!(load_node(&deps, 1, None).metadata.rank, 0);
+
+        let err = edit_body(&mut deps, &env, "alice", 1, "Edited again").unwrap_err();
+        assert!(matches!(err, ContractError::EditNotAllowed { .. }));
+    }
+
+    #[test]
+    fn test_moderators_can_edit_when_allowed() {
+        let (mut deps, env) = create_thread();
+
+        add_moderator(&mut deps, &env, "mod");
+
+        let err = edit_body(&mut deps, &env, "mod", ROOT_ID, "Edited").unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        set_edit_policy(
+            &mut deps,
+            &env,
+            crate::state::models::EditPolicy {
+                window_seconds: Some(60),
+                no_edits_after_reply: true,
+                no_edits_after_vote: true,
+                moderators_can_edit: true,
+            },
+        );
+
+        // Moderators aren't subject to the author's restrictions
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        edit_body(&mut deps, &env, "mod", ROOT_ID, "Edited").unwrap();
+        assert_eq!(load_node(&deps, ROOT_ID, None).body, "Edited");
+
+        let err = edit_body(&mut deps, &env, "bob", ROOT_ID, "Edited").unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+    }
+
+    // ============================================================================
+    // Soft Delete Tests (3 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_soft_delete_keeps_replies() {
+        use crate::state::permissions::Role;
+
+        let (mut deps, env) = create_thread();
+
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        reply_to(&mut deps, &env, "bob", 1).unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Delete { id: 1, hard: None },
+        )
+        .unwrap();
+
+        let node = load_node(&deps, 1, None);
+        assert_eq!(node.body, "");
+        assert_eq!(node.metadata.n_replies, 1);
+        let tombstone = node.deleted.unwrap();
+        assert_eq!(tombstone.deleted_by, Addr::unchecked("alice"));
+        assert_eq!(tombstone.role, Role::Author);
+
+        // Replies remain visible
+        assert_eq!(load_node(&deps, 2, None).body, "Reply to 1");
+        assert_eq!(load_node(&deps, ROOT_ID, None).metadata.n_replies, 1);
+
+        // The tombstone can no longer be interacted with
+        let err = reply_to(&mut deps, &env, "bob", 1).unwrap_err();
+        assert!(matches!(err, ContractError::NodeDeleted { node_id: 1 }));
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[]),
+            ExecuteMsg::Vote(NodeVoteMsg {
+                id: 1,
+                sentiment: crate::msg::Sentiment::Up,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NodeDeleted { node_id: 1 }));
+
+        let err = edit_body(&mut deps, &env, "alice", 1, "Restored").unwrap_err();
+        assert!(matches!(err, ContractError::NodeDeleted { node_id: 1 }));
+    }
+
+    #[test]
+    fn test_moderator_soft_delete_records_role() {
+        use crate::state::permissions::Role;
+
+        let (mut deps, env) = create_thread();
+
+        add_moderator(&mut deps, &env, "mod");
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mod", &[]),
+            ExecuteMsg::Delete { id: 1, hard: None },
+        )
+        .unwrap();
+
+        let tombstone = load_node(&deps, 1, None).deleted.unwrap();
+        assert_eq!(tombstone.role, Role::Moderator);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Delete { id: 1, hard: None },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NodeDeleted { node_id: 1 }));
+    }
+
+    #[test]
+    fn test_hard_delete_requires_moderator() {
+        let (mut deps, env) = create_thread();
+
+        add_moderator(&mut deps, &env, "mod");
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        reply_to(&mut deps, &env, "bob", 1).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mod", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nodes(NodesQueryMsg::ByIds {
+                ids: vec![2],
+                sender: None,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NodeNotFound { .. }));
+        assert_eq!(load_node(&deps, ROOT_ID, None).metadata.n_replies, 0);
+    }
+
+    // ============================================================================
+    // Incremental Deletion Tests (2 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_hard_delete_continues_in_batches() {
+        let (mut deps, env) = create_thread();
+
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        for _ in 0..110 {
+            reply_to(&mut deps, &env, "bob", 1).unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("pending", "true")));
+
+        // The whole subtree is hidden right away
+        assert_eq!(load_node(&deps, ROOT_ID, None).metadata.n_replies, 0);
+        for id in [1, 2, 111] {
+            let err = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nodes(NodesQueryMsg::ByIds {
+                    ids: vec![id],
+                    sender: None,
+                }),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::NodeNotFound { .. }));
+        }
+        let err = reply_to(&mut deps, &env, "bob", 2).unwrap_err();
+        assert!(matches!(err, ContractError::NodeNotFound { .. }));
+
+        // Anyone can finish the job
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ContinueDeletion { limit: None },
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("pending", "false")));
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ContinueDeletion { limit: None },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+    }
+
+    #[test]
+    fn test_root_purge_continues_in_batches() {
+        let (mut deps, env) = create_thread();
+
+        for _ in 0..30 {
+            reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: ROOT_ID,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("pending", "true")));
+
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Thread { sender: None }).unwrap_err();
+        assert!(matches!(err, ContractError::ThreadDeleted {}));
+        let err = reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap_err();
+        assert!(matches!(err, ContractError::ThreadDeleted {}));
+
+        loop {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("anyone", &[]),
+                ExecuteMsg::ContinueDeletion { limit: Some(50) },
+            )
+            .unwrap();
+            if res.attributes.contains(&attr("pending", "false")) {
+                break;
+            }
+        }
+
+        let mut keys = cosmwasm_std::Storage::range_keys(
+            &deps.storage,
+            None,
+            None,
+            cosmwasm_std::Order::Ascending,
+        );
+        assert!(keys.next().is_none());
+    }
+
+    // ============================================================================
+    // Recycle Bin Tests (5 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_restore_soft_deleted_node() {
+        let (mut deps, env) = create_thread();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: None,
+                edit_policy: None,
+                recycle_bin_retention_seconds: Some(60),
+                private_votes: None,
+            }),
+        )
+        .unwrap();
+
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        for id in [1, 2] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("owner", &[]),
+                ExecuteMsg::Delete { id, hard: None },
+            )
+            .unwrap();
+        }
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Restore { id: 1 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Restore { id: 1 },
+        )
+        .unwrap();
+
+        let node = load_node(&deps, 1, None);
+        assert_eq!(node.body, "Reply to 0");
+        assert!(node.deleted.is_none());
+
+        // Node 2 expires from the recycle bin
+        let mut later = env.clone();
+        later.block.time = env.block.time.plus_seconds(60);
+        let err = execute(
+            deps.as_mut(),
+            later,
+            mock_info("owner", &[]),
+            ExecuteMsg::Restore { id: 2 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+    }
+
+    #[test]
+    fn test_restore_hard_deleted_subtree() {
+        let (mut deps, env) = create_thread();
+
+        add_moderator(&mut deps, &env, "mod");
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        reply_to(&mut deps, &env, "bob", 1).unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mod", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+
+        let deleted_query = |sender: &str| {
+            QueryMsg::Nodes(NodesQueryMsg::Deleted {
+                cursor: None,
+                limit: None,
+                sender: Some(Addr::unchecked(sender)),
+            })
+        };
+        let err = query(deps.as_ref(), mock_env(), deleted_query("alice")).unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+
+        let res = query(deps.as_ref(), mock_env(), deleted_query("mod")).unwrap();
+        let response: crate::msg::RecycledNodesPaginationResponse = from_json(&res).unwrap();
+        let ids: Vec<u32> = response.nodes.iter().map(|n| n.metadata.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(response.nodes[1].body, "Reply to 0");
+
+        // Replies can only be restored once their parent is
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mod", &[]),
+            ExecuteMsg::Restore { id: 2 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+
+        for id in [1, 2] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("mod", &[]),
+                ExecuteMsg::Restore { id },
+            )
+            .unwrap();
+        }
+
+        assert_eq!(load_node(&deps, ROOT_ID, None).metadata.n_replies, 1);
+        assert_eq!(load_node(&deps, 1, None).metadata.n_replies, 1);
+        assert_eq!(load_node(&deps, 2, None).body, "Reply to 1");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nodes(NodesQueryMsg::Children {
+                id: 1,
+                order: None,
+                cursor: None,
+                sender: None,
+                limit: None,
+            }),
+        )
+        .unwrap();
+        let children: crate::msg::NodeViewRepliesPaginationResponse = from_json(&res).unwrap();
+        assert_eq!(children.nodes[0].metadata.id, 2);
+    }
+
+    #[test]
+    fn test_deleted_nodes_listed_by_deletion_time() {
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap();
+
+        // The newer node is deleted first
+        let mut later = env.clone();
+        later.block.time = env.block.time.plus_seconds(10);
+        for (id, env) in [(2, &env), (1, &later)] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("owner", &[]),
+                ExecuteMsg::Delete { id, hard: None },
+            )
+            .unwrap();
+        }
+
+        let deleted = |cursor: Option<(u64, u32)>| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nodes(NodesQueryMsg::Deleted {
+                    cursor,
+                    limit: Some(1),
+                    sender: Some(Addr::unchecked("owner")),
+                }),
+            )
+            .unwrap();
+            let response: crate::msg::RecycledNodesPaginationResponse = from_json(&res).unwrap();
+            let ids: Vec<u32> = response.nodes.iter().map(|n| n.metadata.id).collect();
+            (ids, response.cursor)
+        };
+
+        let (ids, cursor) = deleted(None);
+        assert_eq!(ids, vec![1]);
+        assert_eq!(cursor, Some((later.block.time.seconds(), 1)));
+        assert_eq!(
+            deleted(cursor),
+            (vec![2], Some((env.block.time.seconds(), 2)))
+        );
+        assert_eq!(deleted(Some((env.block.time.seconds(), 2))), (vec![], None));
+    }
+
+    #[test]
+    fn test_restore_leaves_sibling_sections_alone() {
+        let (mut deps, env) = create_thread();
+
+        reply_to(&mut deps, &env, "alice", ROOT_ID).unwrap(); // 1
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[]),
+            ExecuteMsg::Reply(NodeReplyMsg {
+                parent_id: ROOT_ID,
+                body: "With sections".to_string(),
+                sections: Some(vec![
+                    Section::Text {
+                        title: None,
+                        body: Some("Section 1".to_string()),
+                    },
+                    Section::Text {
+                        title: None,
+                        body: Some("Section 2".to_string()),
+                    },
+                ]),
+                tags: None,
+                mentions: None,
+            }),
+        )
+        .unwrap(); // 2
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nodes(NodesQueryMsg::Deleted {
+                cursor: None,
+                limit: None,
+                sender: Some(Addr::unchecked("owner")),
+            }),
+        )
+        .unwrap();
+        let response: crate::msg::RecycledNodesPaginationResponse = from_json(&res).unwrap();
+        assert!(response.nodes[0].sections.is_empty());
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Restore { id: 1 },
+        )
+        .unwrap();
+
+        let node = load_node(&deps, 1, None);
+        assert_eq!(node.metadata.n_sections, 0);
+        assert!(node.sections.is_empty());
+        let sibling = load_node(&deps, 2, None);
+        assert_eq!(sibling.metadata.n_sections, 2);
+        assert_eq!(sibling.sections.len(), 2);
+    }
+
+    #[test]
+    fn test_restore_after_limits_are_lowered() {
+        use crate::state::models::LimitOverrides;
+
+        let (mut deps, env) = create_thread();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Reply(NodeReplyMsg {
+                parent_id: ROOT_ID,
+                body: "Tagged".to_string(),
+                sections: None,
+                tags: Some(vec!["rust".to_string(), "wasm".to_string()]),
+                mentions: Some(vec!["@bob".to_string(), "@carol".to_string()]),
+            }),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete { id: 1, hard: None },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: Some(LimitOverrides {
+                    max_title_length: None,
+                    max_body_length: None,
+                    max_tags: Some(1),
+                    max_tag_length: None,
+                    max_mentions: Some(1),
+                    max_sections: None,
+                    max_revisions: None,
+                }),
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: None,
+            }),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Restore { id: 1 },
+        )
+        .unwrap();
+
+        let node = load_node(&deps, 1, None);
+        assert_eq!(node.tags.len(), 2);
+        assert_eq!(node.mentions.len(), 2);
+        assert!(crate::state::storage::IX_TAG_NODE.has(&deps.storage, (&"wasm".to_string(), 1)));
+    }
+
+    // ============================================================================
+    // Child Order Tests (2 tests)
+    // ============================================================================
+
+    fn query_children(
+        deps: &MockDeps,
+        order: crate::msg::ChildOrder,
+        cursor: Option<crate::msg::ChildCursor>,
+        limit: Option<u8>,
+    ) -> Result<crate::msg::NodeViewRepliesPaginationResponse, ContractError> {
+        query_nodes(
+            deps,
+            NodesQueryMsg::Children {
+                id: ROOT_ID,
+                order: Some(order),
+                cursor,
+                sender: None,
+                limit,
+            },
+        )
+    }
+
+    #[test]
+    fn test_children_in_each_order() {
+        use crate::msg::{ChildOrder, Sentiment};
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 2
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 3
+
+        let votes = [
+            (1, "a", Sentiment::Up),
+            (1, "b", Sentiment::Up),
+            (1, "c", Sentiment::Up),
+            (2, "a", Sentiment::Up),
+            (2, "b", Sentiment::Up),
+            (2, "c", Sentiment::Down),
+            (2, "d", Sentiment::Down),
+            (3, "a", Sentiment::Up),
+            (3, "b", Sentiment::Down),
+        ];
+        for (id, voter, sentiment) in votes {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(voter, &[]),
+                ExecuteMsg::Vote(NodeVoteMsg { id, sentiment }),
+            )
+            .unwrap();
+        }
+
+        let ids = |order: ChildOrder| -> Vec<u32> {
+            query_children(&deps, order, None, None)
+                .unwrap()
+                .nodes
+                .iter()
+                .map(|node| node.metadata.id)
+                .collect()
+        };
+
+        assert_eq!(ids(ChildOrder::Top), vec![1, 3, 2]);
+        assert_eq!(ids(ChildOrder::Newest), vec![3, 2, 1]);
+        assert_eq!(ids(ChildOrder::Oldest), vec![1, 2, 3]);
+        assert_eq!(ids(ChildOrder::Controversial), vec![2, 3, 1]);
+
+        // Switching a downvote to an upvote makes node 2 less controversial
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("d", &[]),
+            ExecuteMsg::Vote(NodeVoteMsg {
+                id: 2,
+                sentiment: Sentiment::Up,
+            }),
+        )
+        .unwrap();
+
+        let node = load_node(&deps, 2, None);
+        assert_eq!(node.metadata.n_upvotes, 3);
+        assert_eq!(node.metadata.n_downvotes, 1);
+        assert_eq!(node.metadata.rank, 2);
+
+        let ids = |order: ChildOrder| -> Vec<u32> {
+            query_children(&deps, order, None, None)
+                .unwrap()
+                .nodes
+                .iter()
+                .map(|node| node.metadata.id)
+                .collect()
+        };
+        assert_eq!(ids(ChildOrder::Controversial), vec![3, 2, 1]);
+        assert_eq!(ids(ChildOrder::Top), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_children_cursor_is_typed_by_order() {
+        use crate::msg::{ChildCursor, ChildOrder};
+
+        let (mut deps, env) = create_thread();
+        for _ in 0..3 {
+            reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+        }
+
+        let page = query_children(&deps, ChildOrder::Oldest, None, Some(2)).unwrap();
+        assert_eq!(page.nodes.len(), 2);
+        assert_eq!(page.cursor, Some(ChildCursor::Oldest { id: 2 }));
+
+        let page = query_children(&deps, ChildOrder::Oldest, page.cursor, Some(2)).unwrap();
+        assert_eq!(page.nodes[0].metadata.id, 3);
+        assert_eq!(page.cursor, None);
+
+        let err = query_children(
+            &deps,
+            ChildOrder::Newest,
+            Some(ChildCursor::Oldest { id: 2 }),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidationError { .. }));
+
+        // Deleted replies are removed from every order
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 2,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+        for order in [
+            ChildOrder::Top,
+            ChildOrder::Newest,
+            ChildOrder::Oldest,
+            ChildOrder::Controversial,
+        ] {
+            let page = query_children(&deps, order, None, None).unwrap();
+            assert_eq!(page.nodes.len(), 2);
+        }
+    }
+
+    // ============================================================================
+    // Hot Ranking Tests (1 test)
+    // ============================================================================
+
+    #[test]
+    fn test_hot_order_decays_over_time() {
+        use crate::msg::{ChildCursor, ChildOrder, Sentiment};
+        use crate::state::models::HOT_DECAY_SECONDS;
+
+        let (mut deps, mut env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        for i in 0..10 {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(&format!("voter{}", i), &[]),
+                ExecuteMsg::Vote(NodeVoteMsg {
+                    id: 1,
+                    sentiment: Sentiment::Up,
+                }),
+            )
+            .unwrap();
+        }
+
+        // Two decay periods later, fresh replies outrank a far more popular one
+        env.block.time = env.block.time.plus_seconds(2 * HOT_DECAY_SECONDS as u64);
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 2
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 3
+        for voter in ["voter0", "voter1"] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(voter, &[]),
+                ExecuteMsg::Vote(NodeVoteMsg {
+                    id: 2,
+                    sentiment: Sentiment::Up,
+                }),
+            )
+            .unwrap();
+        }
+
+        let ids = |page: crate::msg::NodeViewRepliesPaginationResponse| -> Vec<u32> {
+            page.nodes.iter().map(|node| node.metadata.id).collect()
+        };
+
+        assert_eq!(
+            ids(query_children(&deps, ChildOrder::Top, None, None).unwrap()),
+            vec![1, 2, 3]
+        );
+
+        let page = query_children(&deps, ChildOrder::Hot, None, Some(2)).unwrap();
+        assert!(matches!(page.cursor, Some(ChildCursor::Hot { id: 3, .. })));
+        assert_eq!(ids(page.clone()), vec![2, 3]);
+
+        let page = query_children(&deps, ChildOrder::Hot, page.cursor, Some(2)).unwrap();
+        assert_eq!(ids(page), vec![1]);
+    }
+
+    // ============================================================================
+    // Best Ranking Tests (1 test)
+    // ============================================================================
+
+    #[test]
+    fn test_best_order_favors_confidence_over_net_votes() {
+        use crate::msg::{ChildOrder, Sentiment};
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 2
+
+        // Node 1 gets 7 up and 2 down, node 2 gets 4 up and none down
+        for i in 0..9 {
+            let sentiment = if i < 7 {
+                Sentiment::Up
+            } else {
+                Sentiment::Down
+            };
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(&format!("voter{}", i), &[]),
+                ExecuteMsg::Vote(NodeVoteMsg { id: 1, sentiment }),
+            )
+            .unwrap();
+        }
+        for i in 0..4 {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(&format!("voter{}", i), &[]),
+                ExecuteMsg::Vote(NodeVoteMsg {
+                    id: 2,
+                    sentiment: Sentiment::Up,
+                }),
+            )
+            .unwrap();
+        }
+
+        let node = load_node(&deps, 1, None);
+        assert_eq!(node.metadata.n_upvotes, 7);
+        assert_eq!(node.metadata.n_downvotes, 2);
+        assert_eq!(node.metadata.rank, 5);
+        // The lower bound of the interval for 7 of 9 is about 0.45
+        assert_eq!(node.metadata.wilson_score() / 10_000, 45);
+
+        let ids = |order: ChildOrder| -> Vec<u32> {
+            query_children(&deps, order, None, None)
+                .unwrap()
+                .nodes
+                .iter()
+                .map(|node| node.metadata.id)
+                .collect()
+        };
+        assert_eq!(ids(ChildOrder::Top), vec![1, 2]);
+        assert_eq!(ids(ChildOrder::Best), vec![2, 1]);
+    }
+
+    // ============================================================================
+    // Subtree Tests (2 tests)
+    // ============================================================================
+
+    fn query_subtree(
+        deps: &MockDeps,
+        max_depth: Option<u8>,
+        max_nodes: Option<u8>,
+        cursor: Option<Vec<crate::msg::ChildCursor>>,
+    ) -> (Vec<u32>, Option<Vec<crate::msg::ChildCursor>>) {
+        let page: crate::msg::NodeViewSubtreePaginationResponse = query_nodes(
+            deps,
+            NodesQueryMsg::Subtree {
+                id: ROOT_ID,
+                max_depth,
+                max_nodes,
+                order: Some(crate::msg::ChildOrder::Oldest),
+                cursor,
+                sender: None,
+            },
+        )
+        .unwrap();
+        (
+            page.nodes.iter().map(|node| node.metadata.id).collect(),
+            page.cursor,
+        )
+    }
+
+    /// Creates replies 1 and 2 to the root, 3 to 1, 4 to 3 and 5 to 2.
+    fn create_conversation() -> (MockDeps, Env) {
+        let (mut deps, env) = create_thread();
+        for parent_id in [ROOT_ID, ROOT_ID, 1, 3, 2] {
+            reply_to(&mut deps, &env, "user1", parent_id).unwrap();
+        }
+        (deps, env)
+    }
+
+    #[test]
+    fn test_subtree_is_listed_in_pre_order() {
+        use crate::msg::ChildCursor;
+
+        let (deps, _) = create_conversation();
+
+        let (ids, cursor) = query_subtree(&deps, None, None, None);
+        assert_eq!(ids, vec![0, 1, 3, 4, 2, 5]);
+        assert_eq!(cursor, None);
+
+        let (ids, cursor) = query_subtree(&deps, None, Some(4), None);
+        assert_eq!(ids, vec![0, 1, 3, 4]);
+        assert_eq!(
+            cursor,
+            Some(vec![
+                ChildCursor::Oldest { id: 1 },
+                ChildCursor::Oldest { id: 3 },
+                ChildCursor::Oldest { id: 4 },
+            ])
+        );
+
+        let (ids, cursor) = query_subtree(&deps, None, Some(4), cursor);
+        assert_eq!(ids, vec![2, 5]);
+        assert_eq!(cursor, None);
+
+        let (ids, _) = query_subtree(&deps, Some(1), None, None);
+        assert_eq!(ids, vec![0, 1, 2]);
+
+        let depths: Vec<u8> = [0, 1, 3, 4]
+            .iter()
+            .map(|id| load_node(&deps, *id, None).metadata.depth)
+            .collect();
+        assert_eq!(depths, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_subtree_cursor_skips_deleted_nodes() {
+        let (mut deps, env) = create_conversation();
+
+        let (ids, cursor) = query_subtree(&deps, None, Some(3), None);
+        assert_eq!(ids, vec![0, 1, 3]);
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 3,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+
+        // Node 4 went along with node 3, so the listing resumes with node 2
+        let (ids, cursor) = query_subtree(&deps, None, Some(3), cursor);
+        assert_eq!(ids, vec![2, 5]);
+        assert_eq!(cursor, None);
+    }
+
+    // ============================================================================
+    // Author History Tests (2 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_query_nodes_by_author() {
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "user2", ROOT_ID).unwrap(); // 2
+        reply_to(&mut deps, &env, "user1", 2).unwrap(); // 3
+        reply_to(&mut deps, &env, "user1", 2).unwrap(); // 4
+
+        // Deleted nodes drop out of the author's history
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("user1", &[]),
+            ExecuteMsg::Delete { id: 4, hard: None },
+        )
+        .unwrap();
+
+        let by_author = |cursor: Option<u32>, limit: Option<u8>| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nodes(NodesQueryMsg::ByAuthor {
+                    address: Addr::unchecked("user1"),
+                    cursor,
+                    limit,
+                    sender: None,
+                }),
+            )
+            .unwrap();
+            let page: crate::msg::NodeViewByAuthorPaginationResponse = from_json(&res).unwrap();
+            let ids: Vec<u32> = page.nodes.iter().map(|node| node.metadata.id).collect();
+            (ids, page.cursor)
+        };
+
+        assert_eq!(by_author(None, None), (vec![3, 1], None));
+        assert_eq!(by_author(None, Some(1)), (vec![3], Some(3)));
+        assert_eq!(by_author(Some(3), Some(1)), (vec![1], Some(1)));
+        assert_eq!(by_author(Some(1), Some(1)), (vec![], None));
+    }
+
+    #[test]
+    fn test_query_nodes_by_author_caps_scan() {
+        use crate::query::nodes::MAX_SCANNED_IDS;
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+
+        // Most of the author's history is deleted
+        let n_deleted = MAX_SCANNED_IDS as u32 + 10;
+        for id in 2..2 + n_deleted {
+            reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("user1", &[]),
+                ExecuteMsg::Delete { id, hard: None },
+            )
+            .unwrap();
+        }
+
+        let by_author = |cursor: Option<u32>| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nodes(NodesQueryMsg::ByAuthor {
+                    address: Addr::unchecked("user1"),
+                    cursor,
+                    limit: None,
+                    sender: None,
+                }),
+            )
+            .unwrap();
+            let page: crate::msg::NodeViewByAuthorPaginationResponse = from_json(&res).unwrap();
+            let ids: Vec<u32> = page.nodes.iter().map(|node| node.metadata.id).collect();
+            (ids, page.cursor)
+        };
+
+        // The scan stops after MAX_SCANNED_IDS nodes and resumes from there
+        let last_scanned = 2 + n_deleted - MAX_SCANNED_IDS as u32;
+        assert_eq!(by_author(None), (vec![], Some(last_scanned)));
+        assert_eq!(by_author(Some(last_scanned)), (vec![1], None));
+    }
+
+    // ============================================================================
+    // Saved Nodes Tests (4 tests)
+    // ============================================================================
+
+    fn query_saved(
+        deps: &MockDeps,
+        address: &str,
+        cursor: Option<(u64, u32)>,
+    ) -> crate::msg::SavedNodesPaginationResponse {
+        query_nodes(
+            deps,
+            NodesQueryMsg::Saved {
+                address: Addr::unchecked(address),
+                cursor,
+                limit: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_saved_nodes_most_recently_saved_first() {
+        use crate::state::storage::NODE_ID_ADDR_2_SAVED_AT;
+
+        let (mut deps, mut env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 2
+        let t0 = env.block.time;
+
+        for (id, delay) in [(1, 0), (2, 10), (1, 20)] {
+            env.block.time = t0.plus_seconds(delay);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("saver", &[]),
+                ExecuteMsg::Save(vec![id]),
+            )
+            .unwrap();
+        }
+
+        // Saving node 1 again keeps the time it was first saved
+        let page = query_saved(&deps, "saver", None);
+        let saved: Vec<(u32, u64)> = page
+            .nodes
+            .iter()
+            .map(|saved| (saved.node.metadata.id, saved.saved_at.seconds()))
+            .collect();
+        assert_eq!(saved, vec![(2, t0.seconds() + 10), (1, t0.seconds())]);
+        assert!(page.nodes[0].node.account.as_ref().unwrap().saved);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("saver", &[]),
+            ExecuteMsg::Unsave(vec![2]),
+        )
+        .unwrap();
+        assert_eq!(query_saved(&deps, "saver", None).nodes.len(), 1);
+
+        // Deleting a node removes it from everyone's saved nodes
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+        assert!(query_saved(&deps, "saver", None).nodes.is_empty());
+        assert!(NODE_ID_ADDR_2_SAVED_AT
+            .prefix(1)
+            .keys(&deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_deleted_nodes_cannot_be_saved() {
+        use crate::state::storage::NODE_ID_ADDR_2_SAVED_AT;
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 2
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user1", &[]),
+            ExecuteMsg::Delete { id: 1, hard: None },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("saver", &[]),
+            ExecuteMsg::Save(vec![1, 2]),
+        )
+        .unwrap();
+
+        let page = query_saved(&deps, "saver", None);
+        assert_eq!(page.nodes.len(), 1);
+        assert_eq!(page.nodes[0].node.metadata.id, 2);
+        assert!(!NODE_ID_ADDR_2_SAVED_AT.has(&deps.storage, (1, &Addr::unchecked("saver"))));
+    }
+
+    #[test]
+    fn test_saved_nodes_caps_scan() {
+        use crate::query::nodes::MAX_SCANNED_IDS;
+
+        let (mut deps, mut env) = create_thread();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("saver", &[]),
+            ExecuteMsg::Save(vec![ROOT_ID]),
+        )
+        .unwrap();
+
+        // Save a large subtree, then hard delete it so that most of it is
+        // still pending deletion
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+        let n_replies = MAX_SCANNED_IDS as u32 + 120;
+        for _ in 0..n_replies {
+            reply_to(&mut deps, &env, "user1", 1).unwrap();
+        }
+        env.block.time = env.block.time.plus_seconds(10);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("saver", &[]),
+            ExecuteMsg::Save((2..2 + n_replies).collect()),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 1,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("pending", "true")));
+
+        // The scan stops after MAX_SCANNED_IDS saves and resumes from there
+        let page = query_saved(&deps, "saver", None);
+        assert!(page.nodes.is_empty());
+        assert!(page.cursor.is_some());
+        let page = query_saved(&deps, "saver", page.cursor);
+        assert_eq!(page.nodes.len(), 1);
+        assert_eq!(page.nodes[0].node.metadata.id, ROOT_ID);
+        assert_eq!(page.cursor, None);
+    }
+
+    #[test]
+    fn test_migration_timestamps_legacy_saves() {
+        let (mut deps, mut env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap(); // 1
+
+        // Roll state back to what 0.0.1 would have written
+        cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw-thread", "0.0.1").unwrap();
+        cw_storage_plus::Map::<(&Addr, u32), bool>::new("ix_addr_saved_id")
+            .save(deps.as_mut().storage, (&Addr::unchecked("saver"), 1), &true)
+            .unwrap();
+
+        env.block.time = env.block.time.plus_seconds(100);
+        crate::contract::migrate(deps.as_mut(), env.clone(), migrate_msg(None)).unwrap();
+
+        let page = query_saved(&deps, "saver", None);
+        assert_eq!(page.nodes.len(), 1);
+        assert_eq!(page.nodes[0].saved_at, env.block.time);
+        assert!(load_node(&deps, 1, Some("saver")).account.unwrap().saved);
+    }
+
+    // ============================================================================
+    // Vote History Tests (2 tests)
+    // ============================================================================
+
+    #[test]
+    fn test_query_votes_by_address() {
+        use crate::msg::{Sentiment, VoteView, VotesPaginationResponse};
+
+        let (mut deps, env) = create_thread();
+        for _ in 0..3 {
+            reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+        }
+
+        // Voting on node 3 twice removes the vote
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("voter", &[]),
+            ExecuteMsg::VoteMany(
+                [
+                    (1, Sentiment::Up),
+                    (2, Sentiment::Down),
+                    (3, Sentiment::Up),
+                    (3, Sentiment::Up),
+                ]
+                .into_iter()
+                .map(|(id, sentiment)| NodeVoteMsg { id, sentiment })
+                .collect(),
+            ),
+        )
+        .unwrap();
+
+        let query_votes = |deps: &MockDeps,
+                           sentiment_filter: Option<Sentiment>,
+                           cursor: Option<u32>,
+                           limit: Option<u8>| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Votes {
+                    address: Addr::unchecked("voter"),
+                    sentiment_filter,
+                    cursor,
+                    limit,
+                },
+            )
+            .unwrap();
+            from_json::<VotesPaginationResponse>(&res).unwrap()
+        };
+
+        let up = |node_id| VoteView {
+            node_id,
+            sentiment: Sentiment::Up,
+        };
+        let down = |node_id| VoteView {
+            node_id,
+            sentiment: Sentiment::Down,
+        };
+
+        let page = query_votes(&deps, None, None, None);
+        assert_eq!(page.votes, vec![down(2), up(1)]);
+        assert_eq!(page.cursor, None);
+
+        let page = query_votes(&deps, Some(Sentiment::Up), None, None);
+        assert_eq!(page.votes, vec![up(1)]);
+
+        let page = query_votes(&deps, None, None, Some(1));
+        assert_eq!(page.votes, vec![down(2)]);
+        let page = query_votes(&deps, None, page.cursor, Some(1));
+        assert_eq!(page.votes, vec![up(1)]);
+
+        // Votes on deleted nodes are forgotten
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::Delete {
+                id: 2,
+                hard: Some(true),
+            },
+        )
+        .unwrap();
+        let page = query_votes(&deps, None, None, None);
+        assert_eq!(page.votes, vec![up(1)]);
+    }
+
+    #[test]
+    fn test_query_votes_caps_scan() {
+        use crate::msg::{Sentiment, VoteView, VotesPaginationResponse};
+        use crate::query::nodes::MAX_SCANNED_IDS;
+
+        let (mut deps, env) = create_thread();
+        let n_downvoted = MAX_SCANNED_IDS as u32 + 10;
+        for _ in 0..1 + n_downvoted {
+            reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+        }
+
+        // Only the oldest node is upvoted
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("voter", &[]),
+            ExecuteMsg::VoteMany(
+                (1..2 + n_downvoted)
+                    .map(|id| NodeVoteMsg {
+                        id,
+                        sentiment: if id == 1 {
+                            Sentiment::Up
+                        } else {
+                            Sentiment::Down
+                        },
+                    })
+                    .collect(),
+            ),
+        )
+        .unwrap();
+
+        let query_upvotes = |cursor: Option<u32>| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Votes {
+                    address: Addr::unchecked("voter"),
+                    sentiment_filter: Some(Sentiment::Up),
+                    cursor,
+                    limit: None,
+                },
+            )
+            .unwrap();
+            from_json::<VotesPaginationResponse>(&res).unwrap()
+        };
+
+        // The cursor is the last vote examined rather than the last returned
+        let last_scanned = 2 + n_downvoted - MAX_SCANNED_IDS as u32;
+        let page = query_upvotes(None);
+        assert!(page.votes.is_empty());
+        assert_eq!(page.cursor, Some(last_scanned));
+        let page = query_upvotes(page.cursor);
+        assert_eq!(
+            page.votes,
+            vec![VoteView {
+                node_id: 1,
+                sentiment: Sentiment::Up,
+            }]
+        );
+        assert_eq!(page.cursor, None);
+    }
+
+    // ============================================================================
+    // Voters Tests (1 test)
+    // ============================================================================
+
+    #[test]
+    fn test_query_voters_unless_private() {
+        use crate::msg::{Sentiment, VoterView, VotersPaginationResponse};
+
+        let (mut deps, env) = create_thread();
+        reply_to(&mut deps, &env, "user1", ROOT_ID).unwrap();
+
+        for (voter, sentiment) in [
+            ("alice", Sentiment::Up),
+            ("bob", Sentiment::Down),
+            ("carol", Sentiment::Up),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(voter, &[]),
+                ExecuteMsg::Vote(NodeVoteMsg { id: 1, sentiment }),
+            )
+            .unwrap();
+        }
+
+        let query_voters = |deps: &MockDeps,
+                            sentiment: Option<Sentiment>,
+                            cursor: Option<Addr>,
+                            limit: Option<u8>| {
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nodes(NodesQueryMsg::Voters {
+                    id: 1,
+                    sentiment,
+                    cursor,
+                    limit,
+                }),
+            )
+            .map(|res| from_json::<VotersPaginationResponse>(&res).unwrap())
+        };
+
+        let voter = |address: &str, sentiment| VoterView {
+            address: Addr::unchecked(address),
+            sentiment,
+        };
+
+        let page = query_voters(&deps, None, None, None).unwrap();
+        assert_eq!(
+            page.voters,
+            vec![
+                voter("alice", Sentiment::Up),
+                voter("bob", Sentiment::Down),
+                voter("carol", Sentiment::Up),
+            ]
+        );
+        assert_eq!(page.cursor, None);
+
+        let page = query_voters(&deps, Some(Sentiment::Up), None, Some(1)).unwrap();
+        assert_eq!(page.voters, vec![voter("alice", Sentiment::Up)]);
+        let page = query_voters(&deps, Some(Sentiment::Up), page.cursor, Some(1)).unwrap();
+        assert_eq!(page.voters, vec![voter("carol", Sentiment::Up)]);
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("owner", &[]),
+            ExecuteMsg::SetConfig(ConfigUpdateMsg {
+                tip_tokens: None,
+                flag_threshold: None,
+                limits: None,
+                edit_policy: None,
+                recycle_bin_retention_seconds: None,
+                private_votes: Some(true),
+            }),
+        )
+        .unwrap();
+
+        // Voters can't be listed, but a known address's votes can be looked up
+        let err = query_voters(&deps, None, None, None).unwrap_err();
+        assert!(matches!(err, ContractError::NotAuthorized { .. }));
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Votes {
+                address: Addr::unchecked("alice"),
+                sentiment_filter: None,
+                cursor: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let response: crate::msg::VotesPaginationResponse = from_json(&res).unwrap();
+        assert_eq!(response.votes.len(), 1);
+        let node = load_node(&deps, 1, Some("alice"));
+        assert!(node.account.unwrap().sentiment.is_some());
+        let node = load_node(&deps, 1, None);
+        assert_eq!(node.metadata.n_upvotes, 2);
+        assert_eq!(node.metadata.n_downvotes, 1);
+    }
+
+    // ============================================================================
+    // Tag Filter Tests (3 tests)
+    // ============================================================================
+
+    fn tagged_reply(
+        tags: &[&str],
+        mentions: &[&str],
+    ) -> ExecuteMsg {
+        ExecuteMsg::Reply(NodeReplyMsg {
+            parent_id: ROOT_ID,
+            body: "Tagged reply".to_string(),
+            sections: None,
+            tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            mentions: Some(mentions.iter().map(|m| m.to_string()).collect()),
+        })
+    }
+
+    fn create_tagged_replies(
+        deps: &mut MockDeps,
+        env: &Env,
+    ) {
+        let replies: [(&[&str], &[&str]); 4] = [
+            (&["bug", "urgent"], &[]),
+            (&["bug"], &[]),
+            (&["urgent", "ui"], &["@carol"]),
+            (&["Bug", "urgent"], &["@carol"]),
+        ];
+        for (tags, mentions) in replies {
+            execute_as(deps, env, "user1", tagged_reply(tags, mentions)).unwrap();
+        }
+    }
+
+    fn query_tagged_ids(
+        deps: &MockDeps,
+        msg: NodesQueryMsg,
+    ) -> (Vec<u32>, Option<u32>) {
+        let response: crate::msg::NodeViewByTagPaginationResponse = query_nodes(deps, msg).unwrap();
+        (
+            response.nodes.iter().map(|node| node.metadata.id).collect(),
+            response.cursor,
+        )
+    }
+
+    #[test]
+    fn test_query_tagged_all_of_and_any_of() {
+        use crate::msg::TagFilter;
+
+        let (mut deps, env) = create_thread();
+        create_tagged_replies(&mut deps, &env);
+
+        let tagged = |tags: TagFilter, mention: Option<&str>| NodesQueryMsg::Tagged {
+            tags,
+            mention: mention.map(|m| m.to_string()),
+            order: None,
+            cursor: None,
+            limit: None,
+            sender: None,
+        };
+        let tags = || vec!["bug".to_string(), "URGENT".to_string()];
+
+        assert_eq!(
+            query_tagged_ids(&deps, tagged(TagFilter::AllOf(tags()), None)),
+            (vec![4, 1], None)
+        );
+        assert_eq!(
+            query_tagged_ids(&deps, tagged(TagFilter::AnyOf(tags()), None)),
+            (vec![4, 3, 2, 1], None)
+        );
+        assert_eq!(
+            query_tagged_ids(&deps, tagged(TagFilter::AllOf(tags()), Some("carol"))),
+            (vec![4], None)
+        );
+        assert_eq!(
+            query_tagged_ids(&deps, tagged(TagFilter::AnyOf(tags()), Some("carol"))),
+            (vec![4, 3], None)
+        );
+
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nodes(tagged(TagFilter::AnyOf(vec![]), None)),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Tag filters must have"));
+    }
+
+    #[test]
+    fn test_query_tagged_order_and_limit() {
+        use crate::msg::{SortOrder, TagFilter};
+
+        let (mut deps, env) = create_thread();
+        create_tagged_replies(&mut deps, &env);
+
+        let with_tag = |cursor: Option<u32>| NodesQueryMsg::WithTag {
+            tag: "bug".to_string(),
+            order: Some(SortOrder::Ascending),
+            cursor,
+            limit: Some(2),
+            sender: None,
+        };
+        assert_eq!(
+            query_tagged_ids(&deps, with_tag(None)),
+            (vec![1, 2], Some(2))
+        );
+        assert_eq!(query_tagged_ids(&deps, with_tag(Some(2))), (vec![4], None));
+
+        let any_of = |cursor: Option<u32>| NodesQueryMsg::Tagged {
+            tags: TagFilter::AnyOf(vec!["ui".to_string(), "bug".to_string()]),
+            mention: None,
+            order: Some(SortOrder::Descending),
+            cursor,
+            limit: Some(3),
+            sender: None,
+        };
+        assert_eq!(
+            query_tagged_ids(&deps, any_of(None)),
+            (vec![4, 3, 2], Some(2))
+        );
+        assert_eq!(query_tagged_ids(&deps, any_of(Some(2))), (vec![1], None));
+    }
+
+    #[test]
+    fn test_query_tagged_caps_scanned_ids() {
+        use crate::msg::TagFilter;
+        use crate::query::nodes::MAX_SCANNED_IDS;
+
+        let (mut deps, env) = create_thread();
+        execute_as(
+            &mut deps,
+            &env,
+            "user1",
+            tagged_reply(&["bug", "urgent"], &[]),
+        )
+        .unwrap();
+        for _ in 0..MAX_SCANNED_IDS + 100 {
+            execute_as(&mut deps, &env, "user1", tagged_reply(&["bug"], &[])).unwrap();
+        }
+
+        let all_of = |cursor: Option<u32>| NodesQueryMsg::Tagged {
+            tags: TagFilter::AllOf(vec!["bug".to_string(), "urgent".to_string()]),
+            mention: None,
+            order: None,
+            cursor,
+            limit: None,
+            sender: None,
+        };
+
+        // The scan stops short of the only match, leaving a cursor to resume from
+        let (ids, cursor) = query_tagged_ids(&deps, all_of(None));
+        assert!(ids.is_empty());
+        assert_eq!(cursor, Some(102));
+        assert_eq!(query_tagged_ids(&deps, all_of(cursor)), (vec![1], None));
+    }
 }
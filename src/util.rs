@@ -7,16 +7,19 @@ use cosmwasm_std::{Order, Storage};
 use crate::{
     error::ContractError,
     state::{
-        models::NodeMetadata,
+        models::{Limits, NodeMetadata},
         storage::{
-            IX_MENTION_NODE, IX_NODE_MENTION, IX_NODE_TAG, IX_TAG_NODE, NODE_ID_2_MENTIONS,
-            NODE_ID_2_METADATA, NODE_ID_2_TAGS, NODE_ID_COUNTER,
+            IX_BEST_CHILD, IX_CHILD, IX_CONTROVERSIAL_CHILD, IX_HOT_CHILD, IX_MENTION_NODE,
+            IX_NODE_MENTION, IX_NODE_TAG, IX_RANKED_CHILD, IX_TAG_NODE, NODE_ID_2_LOCK,
+            NODE_ID_2_MENTIONS, NODE_ID_2_METADATA, NODE_ID_2_TAGS, NODE_ID_2_TOMBSTONE,
+            NODE_ID_COUNTER, PENDING_DELETIONS,
         },
     },
     validation::{validate_mentions, validate_tags},
 };
 
-/// Loads node metadata by ID.
+/// Loads node metadata by ID. Nodes pending deletion are treated as if they
+/// no longer exist.
 ///
 /// If strict is true, returns an error if the node doesn't exist.
 /// If strict is false, returns None if the node doesn't exist.
@@ -25,19 +28,126 @@ pub fn load_node_metadata(
     id: u32,
     strict: bool,
 ) -> Result<Option<NodeMetadata>, ContractError> {
-    let maybe_metadata = NODE_ID_2_METADATA.may_load(store, id)?;
+    let maybe_metadata = match find_pending_deletion(store, id)? {
+        Some(_) => None,
+        None => NODE_ID_2_METADATA.may_load(store, id)?,
+    };
     if strict && maybe_metadata.is_none() {
         return Err(ContractError::NodeNotFound { node_id: id });
     }
     Ok(maybe_metadata)
 }
 
+/// Adds a reply to its parent's children, under each order in which they can
+/// be listed. Must be called again whenever the reply's votes change.
+pub fn index_child(
+    store: &mut dyn Storage,
+    node: &NodeMetadata,
+) -> Result<(), ContractError> {
+    if let Some(parent_id) = node.parent_id {
+        IX_CHILD.save(store, (parent_id, node.id), &true)?;
+        IX_RANKED_CHILD.save(store, (parent_id, node.rank, node.id), &true)?;
+        IX_HOT_CHILD.save(store, (parent_id, node.hot(), node.id), &true)?;
+        IX_BEST_CHILD.save(store, (parent_id, node.wilson_score(), node.id), &true)?;
+        IX_CONTROVERSIAL_CHILD.save(store, (parent_id, node.controversy(), node.id), &true)?;
+    }
+    Ok(())
+}
+
+/// Removes a reply from its parent's children, as indexed by index_child.
+/// The given metadata must be the same as when the reply was last indexed.
+pub fn unindex_child(
+    store: &mut dyn Storage,
+    node: &NodeMetadata,
+) {
+    if let Some(parent_id) = node.parent_id {
+        IX_CHILD.remove(store, (parent_id, node.id));
+        IX_RANKED_CHILD.remove(store, (parent_id, node.rank, node.id));
+        IX_HOT_CHILD.remove(store, (parent_id, node.hot(), node.id));
+        IX_BEST_CHILD.remove(store, (parent_id, node.wilson_score(), node.id));
+        IX_CONTROVERSIAL_CHILD.remove(store, (parent_id, node.controversy(), node.id));
+    }
+}
+
+/// Returns the ID of the nearest locked node in the ancestry of the given node,
+/// including the node itself.
+///
+/// Locking the root node therefore locks the entire thread, while locking any
+/// other node only locks its subtree.
+pub fn find_locked_ancestor(
+    store: &dyn Storage,
+    id: u32,
+) -> Result<Option<u32>, ContractError> {
+    let mut maybe_id = Some(id);
+    while let Some(id) = maybe_id {
+        if NODE_ID_2_LOCK.has(store, id) {
+            return Ok(Some(id));
+        }
+        maybe_id = match NODE_ID_2_METADATA.may_load(store, id)? {
+            Some(metadata) => metadata.parent_id,
+            None => None,
+        };
+    }
+    Ok(None)
+}
+
+/// Aborts if the given node or any of its ancestors is locked.
+pub fn ensure_unlocked(
+    store: &dyn Storage,
+    id: u32,
+) -> Result<(), ContractError> {
+    if let Some(node_id) = find_locked_ancestor(store, id)? {
+        return Err(ContractError::NodeLocked { node_id });
+    }
+    Ok(())
+}
+
+/// Returns the ID of the root of the pending deletion containing the given
+/// node, if any. Nodes pending deletion are treated as already deleted.
+pub fn find_pending_deletion(
+    store: &dyn Storage,
+    id: u32,
+) -> Result<Option<u32>, ContractError> {
+    if PENDING_DELETIONS
+        .keys(store, None, None, Order::Ascending)
+        .next()
+        .is_none()
+    {
+        return Ok(None);
+    }
+    let mut maybe_id = Some(id);
+    while let Some(id) = maybe_id {
+        if PENDING_DELETIONS.has(store, id) {
+            return Ok(Some(id));
+        }
+        maybe_id = match NODE_ID_2_METADATA.may_load(store, id)? {
+            Some(metadata) => metadata.parent_id,
+            None => None,
+        };
+    }
+    Ok(None)
+}
+
+/// Aborts if the given node has been soft deleted or is pending deletion.
+pub fn ensure_not_deleted(
+    store: &dyn Storage,
+    id: u32,
+) -> Result<(), ContractError> {
+    if find_pending_deletion(store, id)?.is_some() {
+        return Err(ContractError::NodeNotFound { node_id: id });
+    }
+    if NODE_ID_2_TOMBSTONE.has(store, id) {
+        return Err(ContractError::NodeDeleted { node_id: id });
+    }
+    Ok(())
+}
+
 /// Generates the next sequential node ID.
 ///
 /// Increments and returns the global node counter. Node IDs start at ROOT_ID (0)
 /// and increase sequentially for each new node created.
 pub fn next_node_id(store: &mut dyn Storage) -> Result<u32, ContractError> {
-    Ok(NODE_ID_COUNTER.update(store, |n| -> Result<_, ContractError> { Ok(n + 1) })?)
+    NODE_ID_COUNTER.update(store, |n| -> Result<_, ContractError> { Ok(n + 1) })
 }
 
 /// Processes and stores tags and mentions for a node.
@@ -55,15 +165,38 @@ pub fn process_tags_and_mentions(
     maybe_mentions: Option<Vec<String>>,
     is_editing: bool,
 ) -> Result<(HashSet<String>, HashSet<String>), ContractError> {
-    // Validate tags and mentions upfront
-    validate_tags(&maybe_tags)?;
-    validate_mentions(&maybe_mentions)?;
+    let tags = update_tags(store, node_id, maybe_tags, is_editing)?;
+    let mentions = update_mentions(store, node_id, maybe_mentions, is_editing)?;
+    Ok((tags, mentions))
+}
 
+/// Replaces a node's tags, maintaining IX_TAG_NODE and IX_NODE_TAG. Passing
+/// None removes all of the node's tags. When editing (is_editing=true),
+/// removes old tags that are no longer present.
+///
+/// Returns the set of processed tags.
+pub fn update_tags(
+    store: &mut dyn Storage,
+    node_id: u32,
+    maybe_tags: Option<Vec<String>>,
+    is_editing: bool,
+) -> Result<HashSet<String>, ContractError> {
+    let limits = Limits::load(store)?;
+    validate_tags(&maybe_tags, &limits)?;
+    index_tags(store, node_id, maybe_tags, is_editing)
+}
+
+/// Same as update_tags, but without validating the tags against the current
+/// limits. Used to put back tags that were valid when they were first set.
+pub fn index_tags(
+    store: &mut dyn Storage,
+    node_id: u32,
+    maybe_tags: Option<Vec<String>>,
+    is_editing: bool,
+) -> Result<HashSet<String>, ContractError> {
     let mut tags: HashSet<String> = HashSet::with_capacity(2);
-    let mut mentions: HashSet<String> = HashSet::with_capacity(2);
 
     NODE_ID_2_TAGS.save(store, node_id, &maybe_tags.clone().unwrap_or_default())?;
-    NODE_ID_2_MENTIONS.save(store, node_id, &maybe_mentions.clone().unwrap_or_default())?;
 
     for token in maybe_tags.unwrap_or_default().iter() {
         let tag = token.to_lowercase();
@@ -74,21 +207,10 @@ pub fn process_tags_and_mentions(
             tags.insert(tag);
         }
     }
-    for token in maybe_mentions.unwrap_or_default().iter() {
-        if let Some(mention) = token.strip_prefix("@") {
-            let mention = mention.to_lowercase();
-            if !mentions.contains(&mention) {
-                let mention = mention.to_owned();
-                IX_MENTION_NODE.save(store, (&mention, node_id), &true)?;
-                IX_NODE_MENTION.save(store, (node_id, &mention), &true)?;
-                mentions.insert(mention);
-            }
-        }
-    }
 
     // We don't enter this block on creation, only update:
     if is_editing {
-        // Remove old tags and mentions
+        // Remove old tags
         for tag in IX_NODE_TAG
             .prefix(node_id)
             .keys(store, None, None, Order::Ascending)
@@ -105,6 +227,55 @@ pub fn process_tags_and_mentions(
             IX_NODE_TAG.remove(store, (node_id, &tag));
             IX_TAG_NODE.remove(store, (&tag, node_id));
         }
+    }
+
+    Ok(tags)
+}
+
+/// Replaces a node's mentions, maintaining IX_MENTION_NODE and
+/// IX_NODE_MENTION. Passing None removes all of the node's mentions. When
+/// editing (is_editing=true), removes old mentions that are no longer present.
+///
+/// Returns the set of processed mentions, without their @ prefix.
+pub fn update_mentions(
+    store: &mut dyn Storage,
+    node_id: u32,
+    maybe_mentions: Option<Vec<String>>,
+    is_editing: bool,
+) -> Result<HashSet<String>, ContractError> {
+    let limits = Limits::load(store)?;
+    validate_mentions(&maybe_mentions, &limits)?;
+    index_mentions(store, node_id, maybe_mentions, is_editing)
+}
+
+/// Same as update_mentions, but without validating the mentions against the
+/// current limits. Used to put back mentions that were valid when they were
+/// first set.
+pub fn index_mentions(
+    store: &mut dyn Storage,
+    node_id: u32,
+    maybe_mentions: Option<Vec<String>>,
+    is_editing: bool,
+) -> Result<HashSet<String>, ContractError> {
+    let mut mentions: HashSet<String> = HashSet::with_capacity(2);
+
+    NODE_ID_2_MENTIONS.save(store, node_id, &maybe_mentions.clone().unwrap_or_default())?;
+
+    for token in maybe_mentions.unwrap_or_default().iter() {
+        if let Some(mention) = token.strip_prefix("@") {
+            let mention = mention.to_lowercase();
+            if !mentions.contains(&mention) {
+                let mention = mention.to_owned();
+                IX_MENTION_NODE.save(store, (&mention, node_id), &true)?;
+                IX_NODE_MENTION.save(store, (node_id, &mention), &true)?;
+                mentions.insert(mention);
+            }
+        }
+    }
+
+    // We don't enter this block on creation, only update:
+    if is_editing {
+        // Remove old mentions
         for mention in IX_NODE_MENTION
             .prefix(node_id)
             .keys(store, None, None, Order::Ascending)
@@ -123,5 +294,5 @@ pub fn process_tags_and_mentions(
         }
     }
 
-    Ok((tags, mentions))
+    Ok(mentions)
 }
@@ -2,34 +2,32 @@
 //!
 //! All validation functions return ContractError::ValidationError on failure.
 //! These enforce limits on content length, count, and format to prevent abuse
-//! and ensure consistent data quality.
+//! and ensure consistent data quality. Content limits are configurable per
+//! thread, so validators take the thread's effective Limits.
 
 use crate::{
     error::ContractError,
-    state::{
-        models::Section,
-        storage::{
-            MAX_BODY_LENGTH, MAX_MENTIONS, MAX_SECTIONS, MAX_TAGS, MAX_TAG_LENGTH,
-            MAX_TITLE_LENGTH,
-        },
-    },
+    state::models::{EditPolicy, FlagThreshold, LimitOverrides, Limits, Section},
 };
 
 /// Validates that a title is non-empty and within length limits.
 ///
-/// Enforces MAX_TITLE_LENGTH (200 characters) to ensure titles remain concise
-/// and displayable in UI lists.
-pub fn validate_title(title: &str) -> Result<(), ContractError> {
+/// Enforces max_title_length (at most MAX_TITLE_LENGTH, 200 characters) to
+/// ensure titles remain concise and displayable in UI lists.
+pub fn validate_title(
+    title: &str,
+    limits: &Limits,
+) -> Result<(), ContractError> {
     if title.trim().is_empty() {
         return Err(ContractError::ValidationError {
             reason: "Title cannot be empty".to_owned(),
         });
     }
-    if title.len() > MAX_TITLE_LENGTH {
+    if title.len() > limits.max_title_length as usize {
         return Err(ContractError::ValidationError {
             reason: format!(
                 "Title length exceeds maximum of {} characters",
-                MAX_TITLE_LENGTH
+                limits.max_title_length
             ),
         });
     }
@@ -38,19 +36,22 @@ pub fn validate_title(title: &str) -> Result<(), ContractError> {
 
 /// Validates that a body is non-empty and within length limits.
 ///
-/// Enforces MAX_BODY_LENGTH (50,000 characters) to prevent storage abuse
-/// while allowing substantial content.
-pub fn validate_body(body: &str) -> Result<(), ContractError> {
+/// Enforces max_body_length (at most MAX_BODY_LENGTH, 50,000 characters) to
+/// prevent storage abuse while allowing substantial content.
+pub fn validate_body(
+    body: &str,
+    limits: &Limits,
+) -> Result<(), ContractError> {
     if body.trim().is_empty() {
         return Err(ContractError::ValidationError {
             reason: "Body cannot be empty".to_owned(),
         });
     }
-    if body.len() > MAX_BODY_LENGTH {
+    if body.len() > limits.max_body_length as usize {
         return Err(ContractError::ValidationError {
             reason: format!(
                 "Body length exceeds maximum of {} characters",
-                MAX_BODY_LENGTH
+                limits.max_body_length
             ),
         });
     }
@@ -60,14 +61,17 @@ pub fn validate_body(body: &str) -> Result<(), ContractError> {
 /// Validates tags: count, length, and alphanumeric format.
 ///
 /// Enforces:
-/// - MAX_TAGS (10) to prevent tag spam
-/// - MAX_TAG_LENGTH (30 characters) per tag
+/// - max_tags (at most MAX_TAGS, 10) to prevent tag spam
+/// - max_tag_length (at most MAX_TAG_LENGTH, 30 characters) per tag
 /// - Alphanumeric format (plus hyphens and underscores) for clean indexing
-pub fn validate_tags(tags: &Option<Vec<String>>) -> Result<(), ContractError> {
+pub fn validate_tags(
+    tags: &Option<Vec<String>>,
+    limits: &Limits,
+) -> Result<(), ContractError> {
     if let Some(tag_list) = tags {
-        if tag_list.len() > MAX_TAGS {
+        if tag_list.len() > limits.max_tags as usize {
             return Err(ContractError::ValidationError {
-                reason: format!("Number of tags exceeds maximum of {}", MAX_TAGS),
+                reason: format!("Number of tags exceeds maximum of {}", limits.max_tags),
             });
         }
 
@@ -77,11 +81,11 @@ pub fn validate_tags(tags: &Option<Vec<String>>) -> Result<(), ContractError> {
                     reason: "Tag cannot be empty".to_owned(),
                 });
             }
-            if tag.len() > MAX_TAG_LENGTH {
+            if tag.len() > limits.max_tag_length as usize {
                 return Err(ContractError::ValidationError {
                     reason: format!(
                         "Tag '{}' exceeds maximum length of {} characters",
-                        tag, MAX_TAG_LENGTH
+                        tag, limits.max_tag_length
                     ),
                 });
             }
@@ -105,14 +109,20 @@ pub fn validate_tags(tags: &Option<Vec<String>>) -> Result<(), ContractError> {
 /// Validates mentions: count and @ prefix format.
 ///
 /// Enforces:
-/// - MAX_MENTIONS (20) to prevent mention spam
+/// - max_mentions (at most MAX_MENTIONS, 20) to prevent mention spam
 /// - All mentions must start with @ symbol
 /// - Mention must have content after the @ symbol
-pub fn validate_mentions(mentions: &Option<Vec<String>>) -> Result<(), ContractError> {
+pub fn validate_mentions(
+    mentions: &Option<Vec<String>>,
+    limits: &Limits,
+) -> Result<(), ContractError> {
     if let Some(mention_list) = mentions {
-        if mention_list.len() > MAX_MENTIONS {
+        if mention_list.len() > limits.max_mentions as usize {
             return Err(ContractError::ValidationError {
-                reason: format!("Number of mentions exceeds maximum of {}", MAX_MENTIONS),
+                reason: format!(
+                    "Number of mentions exceeds maximum of {}",
+                    limits.max_mentions
+                ),
             });
         }
 
@@ -135,87 +145,193 @@ pub fn validate_mentions(mentions: &Option<Vec<String>>) -> Result<(), ContractE
 /// Validates sections: count and basic content limits.
 ///
 /// Enforces:
-/// - MAX_SECTIONS (20) to limit rich content complexity
-/// - Text/Code sections must be non-empty and within MAX_BODY_LENGTH
-/// - Image/Link URLs must be non-empty
-/// - Link text and code language (if specified) must be non-empty
-pub fn validate_sections(sections: &Option<Vec<Section>>) -> Result<(), ContractError> {
+/// - max_sections (at most MAX_SECTIONS, 20) to limit rich content complexity
+/// - Text sections must have a title or body, and code sections a text
+/// - Text bodies and code within max_body_length, and titles within
+///   max_title_length
+/// - Image URIs and link URLs must be non-empty
+/// - Optional fields must be non-empty if specified
+pub fn validate_sections(
+    sections: &Option<Vec<Section>>,
+    limits: &Limits,
+) -> Result<(), ContractError> {
     if let Some(section_list) = sections {
-        if section_list.len() > MAX_SECTIONS {
+        if section_list.len() > limits.max_sections as usize {
             return Err(ContractError::ValidationError {
-                reason: format!("Number of sections exceeds maximum of {}", MAX_SECTIONS),
+                reason: format!(
+                    "Number of sections exceeds maximum of {}",
+                    limits.max_sections
+                ),
             });
         }
 
         for (idx, section) in section_list.iter().enumerate() {
             // Validate section content based on type
             match section {
-                Section::Text(text) => {
-                    if text.trim().is_empty() {
-                        return Err(ContractError::ValidationError {
-                            reason: format!("Section {} text content cannot be empty", idx),
-                        });
-                    }
-                    if text.len() > MAX_BODY_LENGTH {
+                Section::Text { title, body } => {
+                    if title.is_none() && body.is_none() {
                         return Err(ContractError::ValidationError {
-                            reason: format!(
-                                "Section {} text exceeds maximum length of {} characters",
-                                idx, MAX_BODY_LENGTH
-                            ),
+                            reason: format!("Section {} must have a title or body", idx),
                         });
                     }
-                }
-                Section::Image(url) => {
-                    if url.trim().is_empty() {
+                    validate_section_field(idx, "title", title, Some(limits.max_title_length))?;
+                    validate_section_field(idx, "body", body, Some(limits.max_body_length))?;
+                },
+                Section::Image { uri, caption } => {
+                    if uri.trim().is_empty() {
                         return Err(ContractError::ValidationError {
-                            reason: format!("Section {} image URL cannot be empty", idx),
+                            reason: format!("Section {} image URI cannot be empty", idx),
                         });
                     }
-                }
-                Section::Code { lang, text } => {
+                    validate_section_field(idx, "caption", caption, None)?;
+                },
+                Section::Code {
+                    language,
+                    caption,
+                    text,
+                } => {
                     if text.trim().is_empty() {
                         return Err(ContractError::ValidationError {
                             reason: format!("Section {} code content cannot be empty", idx),
                         });
                     }
-                    if text.len() > MAX_BODY_LENGTH {
+                    if text.len() > limits.max_body_length as usize {
                         return Err(ContractError::ValidationError {
                             reason: format!(
                                 "Section {} code exceeds maximum length of {} characters",
-                                idx, MAX_BODY_LENGTH
+                                idx, limits.max_body_length
                             ),
                         });
                     }
-                    if let Some(language) = lang {
-                        if language.trim().is_empty() {
-                            return Err(ContractError::ValidationError {
-                                reason: format!(
-                                    "Section {} code language cannot be empty if specified",
-                                    idx
-                                ),
-                            });
-                        }
-                    }
-                }
-                Section::Link { text, url } => {
+                    validate_section_field(idx, "language", language, None)?;
+                    validate_section_field(idx, "caption", caption, None)?;
+                },
+                Section::Link { url, name } => {
                     if url.trim().is_empty() {
                         return Err(ContractError::ValidationError {
                             reason: format!("Section {} link URL cannot be empty", idx),
                         });
                     }
-                    if let Some(link_text) = text {
-                        if link_text.trim().is_empty() {
-                            return Err(ContractError::ValidationError {
-                                reason: format!(
-                                    "Section {} link text cannot be empty if specified",
-                                    idx
-                                ),
-                            });
-                        }
-                    }
-                }
+                    validate_section_field(idx, "name", name, None)?;
+                },
             }
         }
     }
     Ok(())
 }
+
+/// Validates an optional text field of a section, which must be non-empty if
+/// set and at most max_length bytes long, if given.
+fn validate_section_field(
+    idx: usize,
+    field: &str,
+    value: &Option<String>,
+    max_length: Option<u32>,
+) -> Result<(), ContractError> {
+    if let Some(value) = value {
+        if value.trim().is_empty() {
+            return Err(ContractError::ValidationError {
+                reason: format!("Section {} {} cannot be empty if specified", idx, field),
+            });
+        }
+        if let Some(max_length) = max_length {
+            if value.len() > max_length as usize {
+                return Err(ContractError::ValidationError {
+                    reason: format!(
+                        "Section {} {} exceeds maximum length of {} characters",
+                        idx, field, max_length
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validates a flag threshold.
+///
+/// Enforces a non-zero flag count so that nodes aren't hidden before anyone
+/// has flagged them.
+pub fn validate_flag_threshold(threshold: &FlagThreshold) -> Result<(), ContractError> {
+    if threshold.count == 0 {
+        return Err(ContractError::ValidationError {
+            reason: "Flag threshold count must be greater than zero".to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Validates an edit policy.
+///
+/// Enforces a non-zero edit window, if any, since a zero-length window would
+/// make posts uneditable; use no_edits_after_reply for that instead.
+pub fn validate_edit_policy(policy: &EditPolicy) -> Result<(), ContractError> {
+    if policy.window_seconds == Some(0) {
+        return Err(ContractError::ValidationError {
+            reason: "Edit window must be greater than zero".to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Validates limit overrides against the default limits, which act as hard
+/// ceilings. Length limits must be non-zero; count limits may be zero to
+/// disallow tags, mentions or sections altogether, or to keep no edit
+/// history.
+pub fn validate_limit_overrides(overrides: &LimitOverrides) -> Result<(), ContractError> {
+    let ceilings = Limits::default();
+    validate_limit(
+        "max_title_length",
+        overrides.max_title_length,
+        1,
+        ceilings.max_title_length,
+    )?;
+    validate_limit(
+        "max_body_length",
+        overrides.max_body_length,
+        1,
+        ceilings.max_body_length,
+    )?;
+    validate_limit("max_tags", overrides.max_tags, 0, ceilings.max_tags)?;
+    validate_limit(
+        "max_tag_length",
+        overrides.max_tag_length,
+        1,
+        ceilings.max_tag_length,
+    )?;
+    validate_limit(
+        "max_mentions",
+        overrides.max_mentions,
+        0,
+        ceilings.max_mentions,
+    )?;
+    validate_limit(
+        "max_sections",
+        overrides.max_sections,
+        0,
+        ceilings.max_sections,
+    )?;
+    validate_limit(
+        "max_revisions",
+        overrides.max_revisions,
+        0,
+        ceilings.max_revisions,
+    )?;
+    Ok(())
+}
+
+fn validate_limit(
+    name: &str,
+    value: Option<u32>,
+    min: u32,
+    max: u32,
+) -> Result<(), ContractError> {
+    if let Some(value) = value {
+        if value < min || value > max {
+            return Err(ContractError::ValidationError {
+                reason: format!("{} must be between {} and {}", name, min, max),
+            });
+        }
+    }
+    Ok(())
+}